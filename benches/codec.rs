@@ -0,0 +1,173 @@
+// Benchmarks of the status code encoding and the conversion of dispatch
+// errors. Run with `cargo bench`, CI only builds them with `cargo bench
+// --no-run`.
+//
+// Baseline (release, x86_64, Rust 1.95):
+// - encode/*:           2.2-2.8 ns
+// - encode_vec/*:       23-48 ns
+// - decode_strict/*:    27-30 ns
+// - decode_const/*:     2 ns
+// - decode_fast/*:      3.4-3.8 ns
+// - decode_lenient/*:   20-24 ns, 33-36 ns when going through `u32::encode`
+// - convert/*:          11-12 ns, 21 ns for a registered `Other` message
+// - convert_single_100: 415 ns, convert_batch_100: 387 ns
+// - batch_decode_10k:   218-228 µs, 42 µs with the fast path
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+#[allow(deprecated)]
+use encoding::encode_and_decode_to_pop_api_error;
+use encoding::{
+    all_valid_codes, fast_decode, fast_decode_from_u32, try_decode_from_u32, Converter,
+    DefaultConverter, FungiblesError, ModuleError, PopApiError, UseCaseError, ASSETS_PALLET_INDEX,
+};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::DispatchError;
+
+// Representative errors: a nested use case error, a module error and an
+// unspecified error.
+fn errors() -> [(&'static str, PopApiError); 3] {
+    [
+        (
+            "fungibles",
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+        ),
+        (
+            "module",
+            PopApiError::Module(ModuleError { index: 1, error: 2 }),
+        ),
+        (
+            "unspecified",
+            PopApiError::Unspecified {
+                dispatch_error_index: 3,
+                error_index: 2,
+                error: 1,
+            },
+        ),
+    ]
+}
+
+fn encode(c: &mut Criterion) {
+    for (name, error) in errors() {
+        c.bench_function(&format!("encode/{name}"), |b| {
+            b.iter(|| u32::from(black_box(error)))
+        });
+        // The original encoding through a `Vec`, for comparison.
+        c.bench_function(&format!("encode_vec/{name}"), |b| {
+            b.iter(|| {
+                let mut encoded = black_box(error).encode();
+                encoded.resize(4, 0);
+                u32::decode(&mut &encoded[..]).unwrap()
+            })
+        });
+    }
+}
+
+// The deprecated lenient helper is kept as a point of comparison.
+#[allow(deprecated)]
+fn decode(c: &mut Criterion) {
+    for (name, error) in errors() {
+        let code = u32::from(error);
+        c.bench_function(&format!("decode_strict/{name}"), |b| {
+            b.iter(|| try_decode_from_u32(black_box(code)))
+        });
+        c.bench_function(&format!("decode_const/{name}"), |b| {
+            b.iter(|| fast_decode(black_box(code)))
+        });
+        c.bench_function(&format!("decode_fast/{name}"), |b| {
+            b.iter(|| fast_decode_from_u32(black_box(code)))
+        });
+        c.bench_function(&format!("decode_lenient/{name}"), |b| {
+            b.iter(|| encode_and_decode_to_pop_api_error(black_box(code)))
+        });
+    }
+}
+
+fn convert(c: &mut Criterion) {
+    let module = |index, error| {
+        DispatchError::Module(sp_runtime::ModuleError {
+            index,
+            error: [error, 0, 0, 0],
+            message: None,
+        })
+    };
+    let cases = [
+        ("fungibles", module(ASSETS_PALLET_INDEX, 0)),
+        ("module", module(1, 2)),
+        (
+            "token",
+            DispatchError::Token(sp_runtime::TokenError::Frozen),
+        ),
+        ("other", DispatchError::Other("UnknownFunctionId")),
+    ];
+    for (name, error) in cases {
+        c.bench_function(&format!("convert/{name}"), |b| {
+            b.iter(|| DefaultConverter.convert(black_box(error)))
+        });
+    }
+}
+
+// Converts 100 mixed errors one by one and as a batch.
+fn convert_batch(c: &mut Criterion) {
+    let errors: Vec<DispatchError> = (0..100u8)
+        .map(|i| match i % 4 {
+            0 => DispatchError::Module(sp_runtime::ModuleError {
+                index: ASSETS_PALLET_INDEX,
+                error: [i % 17, 0, 0, 0],
+                message: None,
+            }),
+            1 => DispatchError::BadOrigin,
+            2 => DispatchError::Token(sp_runtime::TokenError::Frozen),
+            _ => DispatchError::Other(""),
+        })
+        .collect();
+    c.bench_function("convert_single_100", |b| {
+        let mut buffer = [PopApiError::Other(0); 100];
+        b.iter(|| {
+            for (error, slot) in black_box(&errors).iter().zip(buffer.iter_mut()) {
+                *slot = DefaultConverter.convert(*error);
+            }
+        })
+    });
+    c.bench_function("convert_batch_100", |b| {
+        let mut buffer = [PopApiError::Other(0); 100];
+        b.iter(|| DefaultConverter.convert_into(black_box(&errors), &mut buffer))
+    });
+}
+
+// Decodes 10k codes cycling through every valid code.
+fn batch_decode(c: &mut Criterion) {
+    let codes: Vec<u32> = all_valid_codes().into_iter().cycle().take(10_000).collect();
+    c.bench_function("batch_decode_10k", |b| {
+        b.iter_batched(
+            || codes.clone(),
+            |codes| {
+                codes
+                    .into_iter()
+                    .filter_map(|code| try_decode_from_u32(code).ok())
+                    .count()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("batch_decode_fast_10k", |b| {
+        b.iter_batched(
+            || codes.clone(),
+            |codes| {
+                codes
+                    .into_iter()
+                    .filter_map(|code| fast_decode_from_u32(code).ok())
+                    .count()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(
+    benches,
+    encode,
+    decode,
+    convert,
+    convert_batch,
+    batch_decode
+);
+criterion_main!(benches);
@@ -0,0 +1,395 @@
+// Procedural macros of the `encoding` crate.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    parse::Parser, parse_macro_input, punctuated::Punctuated, Attribute, Data, DeriveInput, Error,
+    Expr, Fields, Lit, LitInt, Meta, Token, Type,
+};
+
+// The bytes of a status code.
+const STATUS_CODE_LEN: usize = 4;
+// The number of top-level variants of `PopApiError`, the first byte of its
+// status codes is below it. Can't depend on `encoding` for
+// `max_known_variant()`, `test_pop_api_error_variants` of `encoding` keeps
+// both in sync.
+const POP_API_ERROR_VARIANTS: u8 = 16;
+
+// Generates the layout tests of an enum or struct deriving `Encode`,
+// `MaxEncodedLen` and `TypeInfo`: its maximum encoded length is at most
+// `max_len`, the variant indices of its type information are the declared
+// ones, i.e. the `#[codec(index)]` or the position of the variant, and every
+// unit variant encodes to its index. The tests are generated in a
+// `#[cfg(test)]` module next to the type.
+//
+// A variant whose fields alone exceed the bound, e.g. a `u64` with `max_len =
+// 4`, is rejected at compile time already. The length of other types is only
+// known at runtime, which the generated test checks.
+#[proc_macro_attribute]
+pub fn scale_layout_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let expanded = parse_max_len(args.into())
+        .and_then(|max_len| layout_tests(&input, max_len))
+        .unwrap_or_else(Error::into_compile_error);
+    quote!(#input #expanded).into()
+}
+
+fn parse_max_len(args: proc_macro2::TokenStream) -> Result<usize, Error> {
+    let args = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(args)?;
+    let mut max_len = None;
+    for arg in &args {
+        match arg {
+            Meta::NameValue(arg) if arg.path.is_ident("max_len") => {
+                let Expr::Lit(expr) = &arg.value else {
+                    return Err(Error::new_spanned(&arg.value, "expected an integer"));
+                };
+                let Lit::Int(len) = &expr.lit else {
+                    return Err(Error::new_spanned(&expr.lit, "expected an integer"));
+                };
+                max_len = Some(len.base10_parse()?);
+            }
+            arg => return Err(Error::new_spanned(arg, "expected `max_len = <bytes>`")),
+        }
+    }
+    max_len.ok_or_else(|| Error::new(Span::call_site(), "missing `max_len = <bytes>`"))
+}
+
+fn layout_tests(input: &DeriveInput, max_len: usize) -> Result<proc_macro2::TokenStream, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic types are not supported",
+        ));
+    }
+    let name = &input.ident;
+    let module = format_ident!("__scale_layout_test_{}", name);
+    let mut tests = Vec::new();
+    match &input.data {
+        Data::Enum(data) => {
+            let mut indices = Vec::new();
+            let mut unit_variants = Vec::new();
+            for (position, variant) in data.variants.iter().enumerate() {
+                let index = codec_index(&variant.attrs)?.unwrap_or(position as u8);
+                let min_len = 1 + fields_min_len(&variant.fields);
+                if min_len > max_len {
+                    return Err(Error::new_spanned(
+                        variant,
+                        format!(
+                            "`{name}::{}` encodes to at least {min_len} bytes, exceeding \
+                             `max_len = {max_len}`",
+                            variant.ident
+                        ),
+                    ));
+                }
+                let variant_name = variant.ident.to_string();
+                indices.push(quote!((#variant_name, #index)));
+                if matches!(variant.fields, Fields::Unit) {
+                    let ident = &variant.ident;
+                    unit_variants.push(quote! {
+                        assert_eq!(
+                            ::parity_scale_codec::Encode::encode(&super::#name::#ident),
+                            [#index],
+                            "`{}` doesn't encode to its index",
+                            #variant_name
+                        );
+                    });
+                }
+            }
+            tests.push(quote! {
+                #[test]
+                fn test_variant_indices() {
+                    let ty = <super::#name as ::scale_info::TypeInfo>::type_info();
+                    let ::scale_info::TypeDef::Variant(variants) = ty.type_def else {
+                        panic!("type information of an enum expected");
+                    };
+                    let indices: Vec<(&str, u8)> = variants
+                        .variants
+                        .iter()
+                        .map(|variant| (variant.name, variant.index))
+                        .collect();
+                    assert_eq!(indices, [#(#indices),*]);
+                }
+
+                #[test]
+                fn test_unit_variants() {
+                    #(#unit_variants)*
+                }
+            });
+        }
+        Data::Struct(data) => {
+            let min_len = fields_min_len(&data.fields);
+            if min_len > max_len {
+                return Err(Error::new_spanned(
+                    input,
+                    format!(
+                        "`{name}` encodes to at least {min_len} bytes, exceeding `max_len = \
+                         {max_len}`"
+                    ),
+                ));
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(input, "unions are not supported"));
+        }
+    }
+    Ok(quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            #[test]
+            fn test_max_len() {
+                let max_len =
+                    <super::#name as ::parity_scale_codec::MaxEncodedLen>::max_encoded_len();
+                assert!(
+                    max_len <= #max_len,
+                    "`{}` encodes to up to {max_len} bytes, exceeding `max_len = {}`",
+                    stringify!(#name),
+                    #max_len
+                );
+            }
+
+            #(#tests)*
+        }
+    })
+}
+
+// Packs an enum deriving `Encode`, `Decode` and `MaxEncodedLen` into status
+// codes the way `PopApiError` is: the encoding zero padded to four bytes, read
+// as little endian `u32`. Generates `From<T> for u32` and `TryFrom<u32>`, which
+// rejects codes with non-zero padding. With `#[status_code(offset = N)]` the
+// variant byte is shifted by `N`, e.g. to place the codes of a contract's own
+// errors above those of `PopApiError` so that both can be told apart. An offset
+// leaving a variant within the range of `PopApiError` is rejected.
+//
+// `MaxEncodedLen` isn't usable in constants, so only variants whose fields
+// alone exceed four bytes are rejected at compile time, like
+// `#[scale_layout_test]` does. The maximum encoded length of other types is
+// checked by a generated `#[cfg(test)]` test.
+#[proc_macro_derive(ToStatusCode, attributes(status_code))]
+pub fn derive_to_status_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_status_code(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn to_status_code(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic types are not supported",
+        ));
+    }
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(input, "only enums are supported"));
+    };
+    let name = &input.ident;
+    let offset = parse_offset(&input.attrs)?;
+    for (position, variant) in data.variants.iter().enumerate() {
+        let index = codec_index(&variant.attrs)?.unwrap_or(position as u8);
+        let min_len = 1 + fields_min_len(&variant.fields);
+        if min_len > STATUS_CODE_LEN {
+            return Err(Error::new_spanned(
+                variant,
+                format!(
+                    "`{name}::{}` encodes to at least {min_len} bytes, exceeding the \
+                     {STATUS_CODE_LEN} bytes of a status code",
+                    variant.ident
+                ),
+            ));
+        }
+        if let Some((offset, span)) = offset {
+            let code_index = u16::from(index) + u16::from(offset);
+            if code_index < u16::from(POP_API_ERROR_VARIANTS) {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "`{name}::{}` is shifted to variant byte {code_index}, colliding with \
+                         the status codes of `PopApiError`, use an offset of at least \
+                         {POP_API_ERROR_VARIANTS}",
+                        variant.ident
+                    ),
+                ));
+            }
+            if code_index > u16::from(u8::MAX) {
+                return Err(Error::new(
+                    span,
+                    format!(
+                        "`{name}::{}` is shifted to variant byte {code_index}, exceeding {}",
+                        variant.ident,
+                        u8::MAX
+                    ),
+                ));
+            }
+        }
+    }
+    let offset = offset.map_or(0, |(offset, _)| offset);
+    let module = format_ident!("__to_status_code_{}", name);
+    Ok(quote! {
+        impl ::core::convert::From<#name> for u32 {
+            fn from(error: #name) -> u32 {
+                struct Buffer {
+                    bytes: [u8; #STATUS_CODE_LEN],
+                    len: usize,
+                }
+
+                impl ::parity_scale_codec::Output for Buffer {
+                    fn write(&mut self, bytes: &[u8]) {
+                        let end = self.len + bytes.len();
+                        assert!(
+                            end <= #STATUS_CODE_LEN,
+                            "`{}` doesn't fit a status code",
+                            stringify!(#name)
+                        );
+                        self.bytes[self.len..end].copy_from_slice(bytes);
+                        self.len = end;
+                    }
+                }
+
+                let mut buffer = Buffer {
+                    bytes: [0; #STATUS_CODE_LEN],
+                    len: 0,
+                };
+                ::parity_scale_codec::Encode::encode_to(&error, &mut buffer);
+                buffer.bytes[0] += #offset;
+                u32::from_le_bytes(buffer.bytes)
+            }
+        }
+
+        impl ::core::convert::TryFrom<u32> for #name {
+            type Error = ::parity_scale_codec::Error;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                let mut bytes = value.to_le_bytes();
+                bytes[0] = bytes[0]
+                    .checked_sub(#offset)
+                    .ok_or("Status code is below the offset")?;
+                let mut input = &bytes[..];
+                let error = <#name as ::parity_scale_codec::Decode>::decode(&mut input)?;
+                if input.iter().any(|byte| *byte != 0) {
+                    return Err("Status code has non-zero padding".into());
+                }
+                Ok(error)
+            }
+        }
+
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            #[test]
+            fn test_fits_status_code() {
+                let max_len =
+                    <super::#name as ::parity_scale_codec::MaxEncodedLen>::max_encoded_len();
+                assert!(
+                    max_len <= #STATUS_CODE_LEN,
+                    "`{}` encodes to up to {max_len} bytes, exceeding the {} bytes of a \
+                     status code",
+                    stringify!(#name),
+                    #STATUS_CODE_LEN
+                );
+            }
+        }
+    })
+}
+
+// The `#[status_code(offset = N)]` of an enum, with the span of the offset.
+fn parse_offset(attrs: &[Attribute]) -> Result<Option<(u8, Span)>, Error> {
+    let mut offset = None;
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("status_code"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("offset") {
+                let value = meta.value()?.parse::<LitInt>()?;
+                offset = Some((value.base10_parse()?, value.span()));
+                Ok(())
+            } else {
+                Err(meta.error("expected `offset = <variant byte>`"))
+            }
+        })?;
+    }
+    Ok(offset)
+}
+
+// The explicit `#[codec(index = ..)]` of a variant.
+fn codec_index(attrs: &[Attribute]) -> Result<Option<u8>, Error> {
+    let mut index = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("codec")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("index") {
+                index = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.input.peek(Token![=]) {
+                // Other codec attributes with a value, e.g. `crate = ..`.
+                meta.value()?.parse::<Expr>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(index)
+}
+
+// A lower bound of the encoded length of the fields: the length of primitive
+// fields, arrays and tuples of them, zero for any other type.
+fn fields_min_len(fields: &Fields) -> usize {
+    fields
+        .iter()
+        .filter(|field| !has_codec_flag(&field.attrs, "skip"))
+        .map(|field| {
+            if has_codec_flag(&field.attrs, "compact") {
+                1
+            } else {
+                type_min_len(&field.ty)
+            }
+        })
+        .sum()
+}
+
+fn has_codec_flag(attrs: &[Attribute], flag: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("codec"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= meta.path.is_ident(flag);
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                }
+                Ok(())
+            });
+            found
+        })
+}
+
+fn type_min_len(ty: &Type) -> usize {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(ident) = path.path.get_ident() else {
+                return 0;
+            };
+            match ident.to_string().as_str() {
+                "bool" | "u8" | "i8" => 1,
+                "u16" | "i16" => 2,
+                "char" | "u32" | "i32" => 4,
+                "u64" | "i64" => 8,
+                "u128" | "i128" => 16,
+                _ => 0,
+            }
+        }
+        Type::Array(array) => {
+            let len = match &array.len {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Int(len) => len.base10_parse().unwrap_or(0),
+                    _ => 0,
+                },
+                _ => 0,
+            };
+            type_min_len(&array.elem) * len
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().map(type_min_len).sum(),
+        Type::Paren(paren) => type_min_len(&paren.elem),
+        Type::Group(group) => type_min_len(&group.elem),
+        _ => 0,
+    }
+}
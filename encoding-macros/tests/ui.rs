@@ -0,0 +1,9 @@
+// Compile time errors of `#[scale_layout_test]`, `#[derive(ToStatusCode)]` and
+// `define_errors!` of `encoding`, regenerate the expected output with `TRYBUILD=overwrite` after a reviewed
+// change.
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,16 @@
+// `define_errors!` is private to `encoding`, its definition is included
+// directly.
+#[path = "../../../../src/macros.rs"]
+mod macros;
+
+use parity_scale_codec::{Decode, Encode};
+
+macros::define_errors! {
+    #[derive(Debug, PartialEq, Clone, Copy, Encode, Decode)]
+    pub enum Duplicate {
+        A: index 0, "a", "A.";
+        B: index 0, "b", "B.";
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use encoding_macros::scale_layout_test;
+use parity_scale_codec::{Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+#[scale_layout_test(max_len = 4)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub enum Error {
+    Unit,
+    Amount(u64),
+}
+
+#[scale_layout_test(max_len = 4)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub struct Key {
+    pallet: u8,
+    id: [u8; 4],
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use encoding_macros::scale_layout_test;
+use parity_scale_codec::{Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+#[scale_layout_test]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub enum Missing {
+    Unit,
+}
+
+#[scale_layout_test(max_size = 4)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub enum Unknown {
+    Unit,
+}
+
+fn main() {}
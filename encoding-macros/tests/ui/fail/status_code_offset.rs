@@ -0,0 +1,26 @@
+use encoding_macros::ToStatusCode;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+// Overlaps with `PopApiError::UseCase` and `Unspecified`.
+#[derive(Encode, Decode, MaxEncodedLen, ToStatusCode)]
+#[status_code(offset = 14)]
+pub enum Collides {
+    Unit,
+    Other,
+}
+
+#[derive(Encode, Decode, MaxEncodedLen, ToStatusCode)]
+#[status_code(offset = 250)]
+pub enum Overflows {
+    Unit,
+    #[codec(index = 6)]
+    Last,
+}
+
+#[derive(Encode, Decode, MaxEncodedLen, ToStatusCode)]
+#[status_code(shift = 16)]
+pub enum Unknown {
+    Unit,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use encoding_macros::ToStatusCode;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+#[derive(Encode, Decode, MaxEncodedLen, ToStatusCode)]
+pub enum Error {
+    Unit,
+    Amount(u32),
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use encoding_macros::ToStatusCode;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+#[derive(Debug, PartialEq, Encode, Decode, MaxEncodedLen, ToStatusCode)]
+pub enum Standalone {
+    Unit,
+    Payload(u8, u16),
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, MaxEncodedLen, ToStatusCode)]
+#[status_code(offset = 16)]
+pub enum Shifted {
+    Unit,
+    #[codec(index = 239)]
+    Last(u8),
+}
+
+fn main() {
+    assert_eq!(u32::from(Standalone::Payload(1, 2)), 0x0002_0101);
+    assert_eq!(u32::from(Shifted::Last(1)), 0x01ff);
+    assert_eq!(Shifted::try_from(0x01ff), Ok(Shifted::Last(1)));
+}
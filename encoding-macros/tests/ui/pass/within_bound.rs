@@ -0,0 +1,27 @@
+use encoding_macros::scale_layout_test;
+use parity_scale_codec::{Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+#[scale_layout_test(max_len = 4)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub enum Error {
+    Unit,
+    #[codec(index = 5)]
+    Payload(u8, u16),
+    Nested(Inner),
+}
+
+#[scale_layout_test(max_len = 1)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub enum Inner {
+    Leaf,
+}
+
+#[scale_layout_test(max_len = 2)]
+#[derive(Encode, MaxEncodedLen, TypeInfo)]
+pub struct Pair {
+    a: u8,
+    b: u8,
+}
+
+fn main() {}
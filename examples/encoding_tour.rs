@@ -0,0 +1,36 @@
+// Renders the tour of the SCALE encoding as a table, explaining every byte of
+// every value:
+//
+//     cargo run --example encoding_tour --features explain
+use encoding::{explain_encoding, tour::tour_values};
+use parity_scale_codec::Encode;
+
+fn main() {
+    let rows: Vec<_> = tour_values()
+        .iter()
+        .map(|value| {
+            (
+                format!("{value:?}"),
+                format!("{:?}", value.encode()),
+                explain_encoding(value).to_string(),
+            )
+        })
+        .collect();
+    let value_width = rows
+        .iter()
+        .map(|(value, ..)| value.len())
+        .max()
+        .unwrap_or(0);
+    let bytes_width = rows
+        .iter()
+        .map(|(_, bytes, _)| bytes.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:value_width$} | {:bytes_width$} | explanation",
+        "value", "bytes"
+    );
+    for (value, bytes, explanation) in rows {
+        println!("{value:value_width$} | {bytes:bytes_width$} | {explanation}");
+    }
+}
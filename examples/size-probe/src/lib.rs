@@ -0,0 +1,16 @@
+// What a contract does with a status code: decode it and match on a few
+// variants. The size of the resulting wasm is checked against `budget.txt` by
+// `tests/size.rs`.
+use encoding::{try_decode_from_u32, FungiblesError, PopApiError, UseCaseError};
+
+#[no_mangle]
+pub extern "C" fn handle_status_code(code: u32) -> u32 {
+    match try_decode_from_u32(code) {
+        Ok(PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance))) => 1,
+        Ok(PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount))) => 2,
+        Ok(PopApiError::BadOrigin) => 3,
+        Ok(PopApiError::Module(error)) => u32::from(error.index) << 8 | u32::from(error.error),
+        Ok(_) => 4,
+        Err(_) => 5,
+    }
+}
@@ -0,0 +1,9 @@
+// Fuzzes the conversion of a `DispatchError` into a `PopApiError`, run with
+// `cargo +nightly fuzz run conversion -- regressions/conversion`. Inputs that
+// break an invariant should be added to `regressions/conversion`.
+#![no_main]
+
+use encoding::fuzzing::check_conversion_from_bytes;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| check_conversion_from_bytes(data));
@@ -0,0 +1,298 @@
+// The catalogue of errors as seen by other languages and tooling, e.g. the
+// conformance fixtures shared with the TypeScript decoder.
+use crate::{
+    try_decode_from_u32, ArithmeticError, FungiblesError, ModuleError, PopApiError, TokenError,
+    TransactionalError, UseCaseError,
+};
+
+impl PopApiError {
+    // Path of the variant within the error, e.g.
+    // `UseCase::Fungibles::InsufficientBalance`. Payloads are not part of the
+    // path.
+    pub fn path(&self) -> &'static str {
+        use PopApiError::*;
+        match self {
+            Other(_) => "Other",
+            CannotLookup => "CannotLookup",
+            BadOrigin => "BadOrigin",
+            Module(_) => "Module",
+            ConsumerRemaining => "ConsumerRemaining",
+            NoProviders => "NoProviders",
+            TooManyConsumers => "TooManyConsumers",
+            Token(error) => match error {
+                TokenError::FundsUnavailable => "Token::FundsUnavailable",
+                TokenError::OnlyProvider => "Token::OnlyProvider",
+                TokenError::BelowMinimum => "Token::BelowMinimum",
+                TokenError::CannotCreate => "Token::CannotCreate",
+                TokenError::UnknownAsset => "Token::UnknownAsset",
+                TokenError::Frozen => "Token::Frozen",
+                TokenError::Unsupported => "Token::Unsupported",
+                TokenError::CannotCreateHold => "Token::CannotCreateHold",
+                TokenError::NotExpendable => "Token::NotExpendable",
+                TokenError::Blocked => "Token::Blocked",
+            },
+            Arithmetic(error) => match error {
+                ArithmeticError::Underflow => "Arithmetic::Underflow",
+                ArithmeticError::Overflow => "Arithmetic::Overflow",
+                ArithmeticError::DivisionByZero => "Arithmetic::DivisionByZero",
+            },
+            Transactional(error) => match error {
+                TransactionalError::LimitReached => "Transactional::LimitReached",
+                TransactionalError::NoLayer => "Transactional::NoLayer",
+            },
+            Exhausted => "Exhausted",
+            Corruption => "Corruption",
+            Unavailable => "Unavailable",
+            RootNotAllowed => "RootNotAllowed",
+            UseCase(UseCaseError::Fungibles(error)) => match error {
+                FungiblesError::AssetNotLive => "UseCase::Fungibles::AssetNotLive",
+                FungiblesError::BelowMinimum => "UseCase::Fungibles::BelowMinimum",
+                FungiblesError::InsufficientAllowance => {
+                    "UseCase::Fungibles::InsufficientAllowance"
+                }
+                FungiblesError::InsufficientBalance => "UseCase::Fungibles::InsufficientBalance",
+                FungiblesError::InUse => "UseCase::Fungibles::InUse",
+                FungiblesError::MinBalanceZero => "UseCase::Fungibles::MinBalanceZero",
+                FungiblesError::NoAccount => "UseCase::Fungibles::NoAccount",
+                FungiblesError::NoPermission => "UseCase::Fungibles::NoPermission",
+                FungiblesError::Unknown => "UseCase::Fungibles::Unknown",
+                FungiblesError::CannotIncreaseAllowance => {
+                    "UseCase::Fungibles::CannotIncreaseAllowance"
+                }
+            },
+            UseCase(UseCaseError::Module(_)) => "UseCase::Module",
+            Unspecified { .. } => "Unspecified",
+        }
+    }
+}
+
+impl PopApiError {
+    // Short uppercase mnemonic of the variant for terse logging, e.g.
+    // `FUNG_INSUF_BAL`. Mnemonics are unique and must not change once
+    // released.
+    pub fn mnemonic(&self) -> &'static str {
+        use PopApiError::*;
+        match self {
+            Other(_) => "OTHER",
+            CannotLookup => "CANNOT_LOOKUP",
+            BadOrigin => "BAD_ORIGIN",
+            Module(_) => "MODULE",
+            ConsumerRemaining => "CONSUMER_REMAINING",
+            NoProviders => "NO_PROVIDERS",
+            TooManyConsumers => "TOO_MANY_CONSUMERS",
+            Token(error) => match error {
+                TokenError::FundsUnavailable => "TOKEN_FUNDS_UNAVAIL",
+                TokenError::OnlyProvider => "TOKEN_ONLY_PROVIDER",
+                TokenError::BelowMinimum => "TOKEN_BELOW_MIN",
+                TokenError::CannotCreate => "TOKEN_CANNOT_CREATE",
+                TokenError::UnknownAsset => "TOKEN_UNKNOWN_ASSET",
+                TokenError::Frozen => "TOKEN_FROZEN",
+                TokenError::Unsupported => "TOKEN_UNSUPPORTED",
+                TokenError::CannotCreateHold => "TOKEN_CANNOT_CREATE_HOLD",
+                TokenError::NotExpendable => "TOKEN_NOT_EXPENDABLE",
+                TokenError::Blocked => "TOKEN_BLOCKED",
+            },
+            Arithmetic(error) => match error {
+                ArithmeticError::Underflow => "ARITH_UNF",
+                ArithmeticError::Overflow => "ARITH_OVF",
+                ArithmeticError::DivisionByZero => "ARITH_DIV_ZERO",
+            },
+            Transactional(error) => match error {
+                TransactionalError::LimitReached => "TX_LIMIT_REACHED",
+                TransactionalError::NoLayer => "TX_NO_LAYER",
+            },
+            Exhausted => "EXHAUSTED",
+            Corruption => "CORRUPTION",
+            Unavailable => "UNAVAILABLE",
+            RootNotAllowed => "ROOT_NOT_ALLOWED",
+            UseCase(UseCaseError::Fungibles(error)) => match error {
+                FungiblesError::AssetNotLive => "FUNG_ASSET_NOT_LIVE",
+                FungiblesError::BelowMinimum => "FUNG_BELOW_MIN",
+                FungiblesError::InsufficientAllowance => "FUNG_INSUF_ALLOWANCE",
+                FungiblesError::InsufficientBalance => "FUNG_INSUF_BAL",
+                FungiblesError::InUse => "FUNG_IN_USE",
+                FungiblesError::MinBalanceZero => "FUNG_MIN_BAL_ZERO",
+                FungiblesError::NoAccount => "FUNG_NO_ACCOUNT",
+                FungiblesError::NoPermission => "FUNG_NO_PERMISSION",
+                FungiblesError::Unknown => "FUNG_UNKNOWN",
+                FungiblesError::CannotIncreaseAllowance => "FUNG_ALLOWANCE_OVERFLOW",
+            },
+            UseCase(UseCaseError::Module(_)) => "USE_CASE_MODULE",
+            Unspecified { .. } => "UNSPECIFIED",
+        }
+    }
+}
+
+// Escapes a string for use within a JSON string literal.
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Codes that don't decode, included in the conformance fixtures with the
+// expected decoding error.
+const INVALID_CODES: [u32; 2] = [u32::MAX, u32::from_le_bytes([14, 0, 3, 7])];
+
+// The conformance fixtures shared with decoders in other languages: a JSON
+// array with an entry `{ "code", "path", "message" }` for every variant, plus
+// edge cases and invalid codes with their `error`. The output is
+// deterministic so that it can be compared in CI.
+pub fn conformance_fixtures() -> String {
+    let mut errors = PopApiError::all_variants();
+    errors.extend([
+        PopApiError::Other(u8::MAX),
+        PopApiError::Module(ModuleError {
+            index: u8::MAX,
+            error: u8::MAX,
+        }),
+        PopApiError::Unspecified {
+            dispatch_error_index: u8::MAX,
+            error_index: u8::MAX,
+            error: u8::MAX,
+        },
+    ]);
+    let mut entries: Vec<String> = errors
+        .into_iter()
+        .map(|error| {
+            format!(
+                "  {{ \"code\": {}, \"path\": \"{}\", \"message\": \"{}\" }}",
+                u32::from(error),
+                error.path(),
+                json_escape(&error.to_string())
+            )
+        })
+        .collect();
+    entries.extend(INVALID_CODES.into_iter().map(|code| {
+        // Only the message, decoders in other languages don't report positions.
+        let error = try_decode_from_u32(code).unwrap_err().error;
+        format!(
+            "  {{ \"code\": {code}, \"error\": \"{}\" }}",
+            json_escape(&error.to_string())
+        )
+    }));
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+// A table of every variant with the columns `code | class | mnemonic |
+// message`, for support tools helping users to map codes.
+pub fn readable_table() -> String {
+    let rows: Vec<[String; 4]> = PopApiError::all_variants()
+        .into_iter()
+        .map(|error| {
+            let report = error.report();
+            [
+                report.code.to_string(),
+                report.class.to_string(),
+                error.mnemonic().to_string(),
+                report.message.into_owned(),
+            ]
+        })
+        .collect();
+    let header = ["code", "class", "mnemonic", "message"].map(String::from);
+    let mut widths = [0; 3];
+    for row in rows.iter().chain([&header]) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut table = String::new();
+    for [code, class, mnemonic, message] in [header].iter().chain(&rows) {
+        table.push_str(&format!(
+            "{code:>0$} | {class:<1$} | {mnemonic:<2$} | {message}\n",
+            widths[0], widths[1], widths[2]
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_are_unique() {
+        let variants = PopApiError::all_variants();
+        for (i, error) in variants.iter().enumerate() {
+            assert!(
+                variants[i + 1..]
+                    .iter()
+                    .all(|other| other.path() != error.path()),
+                "duplicate path {}",
+                error.path()
+            );
+        }
+    }
+
+    #[test]
+    fn test_mnemonics_are_unique() {
+        let variants = PopApiError::all_variants();
+        for (i, error) in variants.iter().enumerate() {
+            let mnemonic = error.mnemonic();
+            assert!(
+                mnemonic.chars().all(|c| c.is_ascii_uppercase() || c == '_'),
+                "invalid mnemonic {mnemonic}"
+            );
+            assert!(
+                variants[i + 1..]
+                    .iter()
+                    .all(|other| other.mnemonic() != mnemonic),
+                "duplicate mnemonic {mnemonic}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mnemonic() {
+        use PopApiError::*;
+        assert_eq!(
+            UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)).mnemonic(),
+            "FUNG_INSUF_BAL"
+        );
+        assert_eq!(
+            Arithmetic(ArithmeticError::Overflow).mnemonic(),
+            "ARITH_OVF"
+        );
+        assert_eq!(Token(TokenError::Frozen).mnemonic(), "TOKEN_FROZEN");
+        assert_eq!(Other(7).mnemonic(), "OTHER");
+    }
+
+    // The fixtures consumed by other languages are generated from the
+    // catalogue, run with `UPDATE_SNAPSHOTS=1` to regenerate them after a
+    // reviewed change.
+    #[test]
+    fn test_conformance_fixtures() {
+        const FIXTURES: &str = "conformance/fixtures.json";
+        let generated = conformance_fixtures();
+        assert_eq!(generated, conformance_fixtures(), "not deterministic");
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(FIXTURES, &generated).unwrap();
+            return;
+        }
+        assert_eq!(
+            generated,
+            include_str!("../conformance/fixtures.json"),
+            "catalogue changed, run with `UPDATE_SNAPSHOTS=1` and review {FIXTURES}"
+        );
+    }
+
+    #[test]
+    fn test_readable_table() {
+        let table = readable_table();
+        assert_eq!(table.lines().count(), PopApiError::all_variants().len() + 1);
+        assert!(table
+            .starts_with("    code | class             | mnemonic                 | message\n"));
+        assert!(table.contains(
+            "\n  196622 | UseCase           | FUNG_INSUF_BAL           | fungibles error: not \
+             enough balance to fulfill the request\n"
+        ));
+    }
+}
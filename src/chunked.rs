@@ -0,0 +1,129 @@
+// Decoding from data that is exposed in chunks rather than one contiguous
+// slice, e.g. by chain extensions or host functions with a read callback.
+use parity_scale_codec::{Error, Input};
+
+// `Input` over a sequence of byte slices, read as if they were concatenated,
+// so that values can be decoded without first copying the chunks into a
+// `Vec`. Values may span any number of chunks and empty chunks are skipped.
+pub struct ChunkedInput<'a> {
+    current: &'a [u8],
+    rest: core::slice::Iter<'a, &'a [u8]>,
+}
+
+impl<'a> ChunkedInput<'a> {
+    pub fn new(chunks: &'a [&'a [u8]]) -> Self {
+        Self {
+            current: &[],
+            rest: chunks.iter(),
+        }
+    }
+}
+
+impl Input for ChunkedInput<'_> {
+    // The sum of the unread bytes of all chunks, the remaining length is
+    // always known.
+    fn remaining_len(&mut self) -> Result<Option<usize>, Error> {
+        let rest: usize = self.rest.as_slice().iter().map(|chunk| chunk.len()).sum();
+        Ok(Some(self.current.len() + rest))
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), Error> {
+        let mut filled = 0;
+        while filled < into.len() {
+            if self.current.is_empty() {
+                self.current = self.rest.next().ok_or("Not enough data to fill buffer")?;
+                continue;
+            }
+            let len = self.current.len().min(into.len() - filled);
+            into[filled..filled + len].copy_from_slice(&self.current[..len]);
+            self.current = &self.current[len..];
+            filled += len;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PopApiError;
+    use parity_scale_codec::{Decode, Encode};
+
+    // Decodes `T` from `bytes` split at the given points.
+    fn decode_split<T: Decode>(bytes: &[u8], splits: &[usize]) -> Result<(T, usize), Error> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        for &split in splits {
+            chunks.push(&bytes[start..split]);
+            start = split;
+        }
+        chunks.push(&bytes[start..]);
+        let mut input = ChunkedInput::new(&chunks);
+        assert_eq!(input.remaining_len(), Ok(Some(bytes.len())));
+        let value = T::decode(&mut input)?;
+        Ok((value, input.remaining_len()?.unwrap()))
+    }
+
+    #[test]
+    fn test_every_split() {
+        for error in PopApiError::all_variants() {
+            let encoded = error.encode();
+            for i in 0..=encoded.len() {
+                for j in i..=encoded.len() {
+                    assert_eq!(decode_split(&encoded, &[i, j]), Ok((error, 0)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_chunks() {
+        let mut input = ChunkedInput::new(&[]);
+        assert_eq!(input.remaining_len(), Ok(Some(0)));
+        assert!(PopApiError::decode(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let encoded = PopApiError::Module(crate::ModuleError { index: 1, error: 2 }).encode();
+        let chunks: [&[u8]; 2] = [&encoded[..1], &encoded[1..2]];
+        let mut input = ChunkedInput::new(&chunks);
+        // The same error as decoding the concatenated chunks.
+        let error = PopApiError::decode(&mut &encoded[..2]).unwrap_err();
+        assert_eq!(PopApiError::decode(&mut input), Err(error));
+    }
+
+    proptest::proptest! {
+        // Output payloads of any length decode the same regardless of where
+        // they are split, leaving trailing data unread.
+        #[test]
+        fn split_payload(
+            payload: Result<Vec<u8>, (u8, u8)>,
+            trailing: Vec<u8>,
+            splits: Vec<proptest::sample::Index>,
+        ) {
+            let mut bytes = payload.encode();
+            bytes.extend(&trailing);
+            let mut splits: Vec<_> = splits.iter().map(|i| i.index(bytes.len() + 1)).collect();
+            splits.sort();
+            proptest::prop_assert_eq!(
+                decode_split(&bytes, &splits),
+                Ok((payload, trailing.len()))
+            );
+        }
+
+        // Chunks ending mid-value are an error, wherever they are split.
+        #[test]
+        fn split_truncated(
+            payload: Result<Vec<u8>, (u8, u8)>,
+            cut: proptest::sample::Index,
+            splits: Vec<proptest::sample::Index>,
+        ) {
+            let encoded = payload.encode();
+            let bytes = &encoded[..cut.index(encoded.len())];
+            let mut splits: Vec<_> = splits.iter().map(|i| i.index(bytes.len() + 1)).collect();
+            splits.sort();
+            proptest::prop_assert!(decode_split::<Result<Vec<u8>, (u8, u8)>>(bytes, &splits).is_err());
+        }
+    }
+}
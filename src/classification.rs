@@ -0,0 +1,456 @@
+// Classification of errors, helping tooling and dApps to decide how to handle
+// or present an error.
+use crate::{FungiblesError, PopApiError, UseCaseError};
+
+// The action a user can take to resolve an error.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UserAction {
+    /// The account has to be created, e.g. by funding it.
+    CreateAccount,
+    /// The ID is already taken, a different one has to be chosen.
+    ChooseDifferentId,
+    /// The account references too many other things, e.g. assets or
+    /// contracts, some of which have to be removed first.
+    RemoveDependencies,
+    /// The account doesn't hold enough funds and has to be topped up.
+    TopUp,
+    /// The spender has to be approved for a larger amount by the owner.
+    IncreaseAllowance,
+    /// The amount is too large, a smaller one has to be used.
+    ReduceAmount,
+    /// No asset exists with the given ID, the ID has to be checked, e.g. for
+    /// a typo or an asset on another chain.
+    CheckAssetId,
+}
+
+// Where an error can originate from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorOrigin {
+    /// Only produced by the runtime.
+    Chain,
+    /// Reserved for errors only produced by the contract itself. `origin`
+    /// never returns it, as none of the current variants is exclusive to
+    /// contracts. It exists so that matches on the origin stay valid once a
+    /// variant is.
+    Contract,
+    /// Produced by the runtime or by the contract, e.g. when validating input
+    /// before calling into the runtime.
+    Either,
+}
+
+// The roles of the assets pallet's permission model.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PermissionKind {
+    /// Manages the asset's accounts, e.g. minting and burning.
+    Admin,
+    /// Mints new units of the asset.
+    Issuer,
+    /// Freezes and thaws accounts of the asset.
+    Freezer,
+    /// Owns the asset and assigns the other roles.
+    Owner,
+}
+
+impl PopApiError {
+    // Whether the error is caused by a missing account.
+    pub fn is_account_error(&self) -> bool {
+        use PopApiError::*;
+        matches!(
+            self,
+            NoProviders | UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount))
+        )
+    }
+
+    // Whether the error is caused by the spender not being approved for the
+    // amount, as opposed to the owner lacking the balance.
+    pub fn is_allowance_error(&self) -> bool {
+        matches!(
+            self,
+            PopApiError::UseCase(UseCaseError::Fungibles(
+                FungiblesError::InsufficientAllowance
+            ))
+        )
+    }
+
+    // Whether the call likely requires a signed origin, so that a dApp can
+    // prompt the user to sign. This is best effort: the status code doesn't
+    // tell which origin was required, `BadOrigin` is returned for any origin
+    // check that failed.
+    pub fn requires_signed_origin(&self) -> bool {
+        matches!(self, PopApiError::BadOrigin | PopApiError::RootNotAllowed)
+    }
+
+    // Whether the error is caused by the reference counts of an account, i.e.
+    // its consumers and providers.
+    pub fn is_reference_count_error(&self) -> bool {
+        use PopApiError::*;
+        matches!(self, ConsumerRemaining | NoProviders | TooManyConsumers)
+    }
+
+    // The role the signing account was missing, which is always `None`. The
+    // assets pallet returns the same `NoPermission` error for every role check,
+    // so the conversion can't tell which role was missing. A role would fit the
+    // budget as payload of `NoPermission`, i.e. `[14, 0, 7, role]`, but adding
+    // it would change how released codes decode. `NoPermission` therefore stays
+    // opaque until the pallet distinguishes the roles.
+    pub fn missing_role(&self) -> Option<PermissionKind> {
+        None
+    }
+
+    // Whether the message of the error can be shown to end users verbatim.
+    // Otherwise tooling should show a generic "internal error" and log the
+    // error: `Corruption` and `Unavailable` expose the chain's internals and
+    // `Unspecified` errors need an internal lookup to mean anything.
+    pub fn is_client_safe(&self) -> bool {
+        use PopApiError::*;
+        match self {
+            Corruption | Unavailable | Unspecified { .. } => false,
+            Other(_) | CannotLookup | BadOrigin | Module(_) | ConsumerRemaining | NoProviders
+            | TooManyConsumers | Token(_) | Arithmetic(_) | Transactional(_) | Exhausted
+            | RootNotAllowed | UseCase(_) => true,
+        }
+    }
+
+    // Where the error can originate from, helping tooling to attribute blame.
+    // `Other` and use case errors can be returned by contracts as well, all
+    // other variants are only produced by the runtime's conversion.
+    pub fn origin(&self) -> ErrorOrigin {
+        use PopApiError::*;
+        match self {
+            Other(_) | UseCase(_) => ErrorOrigin::Either,
+            CannotLookup
+            | BadOrigin
+            | Module(_)
+            | ConsumerRemaining
+            | NoProviders
+            | TooManyConsumers
+            | Token(_)
+            | Arithmetic(_)
+            | Transactional(_)
+            | Exhausted
+            | Corruption
+            | Unavailable
+            | RootNotAllowed
+            | Unspecified { .. } => ErrorOrigin::Chain,
+        }
+    }
+}
+
+// Declares the HTTP status, gRPC status and user action of every error in a
+// single table, generating `http_status()`, `grpc_status()` and
+// `user_action()`. The generated matches are exhaustive, so a new variant has
+// to be classified in all three at once.
+macro_rules! classification_table {
+    ($($pattern:pat => http $http:literal, grpc $grpc:literal, action $action:expr;)*) => {
+        impl PopApiError {
+            // HTTP status code for REST gateways in front of a chain.
+            pub fn http_status(&self) -> u16 {
+                use FungiblesError::*;
+                use PopApiError::*;
+                match self {
+                    $($pattern => $http,)*
+                }
+            }
+
+            // Canonical gRPC status code for RPC gateways in front of a chain.
+            pub fn grpc_status(&self) -> u8 {
+                use FungiblesError::*;
+                use PopApiError::*;
+                match self {
+                    $($pattern => $grpc,)*
+                }
+            }
+
+            // The action a user can take to resolve the error, `None` if there
+            // is nothing the user can do about it.
+            pub fn user_action(&self) -> Option<UserAction> {
+                use FungiblesError::*;
+                use PopApiError::*;
+                use UserAction::*;
+                match self {
+                    $($pattern => $action,)*
+                }
+            }
+        }
+    };
+}
+
+// HTTP status codes:
+// - 402 Payment Required: `InsufficientBalance` and `BelowMinimum`.
+// - 403 Forbidden: `BadOrigin`, `RootNotAllowed` and `NoPermission`.
+// - 404 Not Found: `CannotLookup`, `Unknown` and `NoAccount`.
+// - 500 Internal Server Error: arithmetic, transactional and corruption
+//   errors, as well as errors that weren't converted to a known error.
+// - 503 Service Unavailable: `Exhausted` and `Unavailable`.
+// - 400 Bad Request: any other error caused by the request.
+//
+// gRPC status codes: 2 Unknown, 3 InvalidArgument, 5 NotFound, 6 AlreadyExists,
+// 7 PermissionDenied, 8 ResourceExhausted, 9 FailedPrecondition, 11 OutOfRange,
+// 13 Internal, 14 Unavailable and 15 DataLoss.
+classification_table! {
+    Other(_) => http 500, grpc 2, action None;
+    CannotLookup => http 404, grpc 5, action None;
+    BadOrigin => http 403, grpc 7, action None;
+    Module(_) => http 500, grpc 2, action None;
+    ConsumerRemaining => http 400, grpc 9, action None;
+    NoProviders => http 400, grpc 9, action Some(CreateAccount);
+    TooManyConsumers => http 400, grpc 9, action Some(RemoveDependencies);
+    Token(_) => http 400, grpc 9, action None;
+    Arithmetic(_) => http 500, grpc 13, action None;
+    Transactional(_) => http 500, grpc 13, action None;
+    Exhausted => http 503, grpc 8, action None;
+    Corruption => http 500, grpc 15, action None;
+    Unavailable => http 503, grpc 14, action None;
+    RootNotAllowed => http 403, grpc 7, action None;
+    UseCase(UseCaseError::Fungibles(AssetNotLive)) => http 400, grpc 9, action None;
+    UseCase(UseCaseError::Fungibles(BelowMinimum)) => http 402, grpc 9, action None;
+    UseCase(UseCaseError::Fungibles(InsufficientAllowance)) => http 400, grpc 9, action Some(IncreaseAllowance);
+    UseCase(UseCaseError::Fungibles(InsufficientBalance)) => http 402, grpc 9, action Some(TopUp);
+    UseCase(UseCaseError::Fungibles(InUse)) => http 400, grpc 6, action Some(ChooseDifferentId);
+    UseCase(UseCaseError::Fungibles(MinBalanceZero)) => http 400, grpc 3, action None;
+    UseCase(UseCaseError::Fungibles(NoAccount)) => http 404, grpc 5, action Some(CreateAccount);
+    UseCase(UseCaseError::Fungibles(NoPermission)) => http 403, grpc 7, action None;
+    UseCase(UseCaseError::Fungibles(Unknown)) => http 404, grpc 5, action Some(CheckAssetId);
+    UseCase(UseCaseError::Fungibles(CannotIncreaseAllowance)) => http 400, grpc 11, action Some(ReduceAmount);
+    UseCase(UseCaseError::Module(_)) => http 500, grpc 2, action None;
+    Unspecified { .. } => http 500, grpc 2, action None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_account_classification() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        assert!(error.is_account_error());
+        assert_eq!(error.user_action(), Some(UserAction::CreateAccount));
+        assert_eq!(
+            error.to_string(),
+            "fungibles error: the account to alter does not exist"
+        );
+        assert!(PopApiError::NoProviders.is_account_error());
+        assert_eq!(
+            PopApiError::NoProviders.user_action(),
+            Some(UserAction::CreateAccount)
+        );
+    }
+
+    #[test]
+    fn test_other_errors_are_no_account_errors() {
+        let error =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        assert!(!error.is_account_error());
+        assert!(!PopApiError::BadOrigin.is_account_error());
+        assert_eq!(PopApiError::BadOrigin.user_action(), None);
+    }
+
+    #[test]
+    fn test_http_status() {
+        let fungibles = |error| PopApiError::UseCase(UseCaseError::Fungibles(error));
+        assert_eq!(PopApiError::BadOrigin.http_status(), 403);
+        assert_eq!(fungibles(FungiblesError::NoPermission).http_status(), 403);
+        assert_eq!(fungibles(FungiblesError::Unknown).http_status(), 404);
+        assert_eq!(fungibles(FungiblesError::NoAccount).http_status(), 404);
+        assert_eq!(
+            fungibles(FungiblesError::InsufficientBalance).http_status(),
+            402
+        );
+        assert_eq!(fungibles(FungiblesError::BelowMinimum).http_status(), 402);
+        assert_eq!(fungibles(FungiblesError::InUse).http_status(), 400);
+        assert_eq!(
+            PopApiError::Arithmetic(crate::ArithmeticError::Overflow).http_status(),
+            500
+        );
+        assert_eq!(PopApiError::Corruption.http_status(), 500);
+        assert_eq!(PopApiError::Exhausted.http_status(), 503);
+        assert_eq!(PopApiError::Unavailable.http_status(), 503);
+    }
+
+    #[test]
+    fn test_grpc_status() {
+        let fungibles = |error| PopApiError::UseCase(UseCaseError::Fungibles(error));
+        assert_eq!(PopApiError::BadOrigin.grpc_status(), 7);
+        assert_eq!(fungibles(FungiblesError::Unknown).grpc_status(), 5);
+        assert_eq!(fungibles(FungiblesError::InUse).grpc_status(), 6);
+        assert_eq!(
+            fungibles(FungiblesError::InsufficientBalance).grpc_status(),
+            9
+        );
+        assert_eq!(PopApiError::Exhausted.grpc_status(), 8);
+        assert_eq!(PopApiError::Unavailable.grpc_status(), 14);
+        assert_eq!(PopApiError::Corruption.grpc_status(), 15);
+    }
+
+    // Every variant is classified in all three mappings with a valid status.
+    #[test]
+    fn test_classification_table_is_complete() {
+        for error in PopApiError::all_variants() {
+            assert!((400..600).contains(&error.http_status()), "{error:?}");
+            assert!(error.grpc_status() <= 16, "{error:?}");
+            // The gRPC status is an error status, as is the HTTP status.
+            assert_ne!(error.grpc_status(), 0, "{error:?}");
+        }
+    }
+
+    // In a `transferFrom` the owner lacking the balance and the spender lacking
+    // the approval require different actions from different users.
+    #[test]
+    fn test_balance_and_allowance_classification() {
+        let balance =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        let allowance = PopApiError::UseCase(UseCaseError::Fungibles(
+            FungiblesError::InsufficientAllowance,
+        ));
+        assert_eq!(balance.user_action(), Some(UserAction::TopUp));
+        assert_eq!(allowance.user_action(), Some(UserAction::IncreaseAllowance));
+        assert!(!balance.is_allowance_error());
+        assert!(allowance.is_allowance_error());
+        assert_eq!(
+            balance.to_string(),
+            "fungibles error: not enough balance to fulfill the request"
+        );
+        assert_eq!(
+            allowance.to_string(),
+            "fungibles error: not enough allowance to fulfill the request"
+        );
+    }
+
+    #[test]
+    fn test_requires_signed_origin() {
+        assert!(PopApiError::BadOrigin.requires_signed_origin());
+        assert!(PopApiError::RootNotAllowed.requires_signed_origin());
+        assert!(!PopApiError::CannotLookup.requires_signed_origin());
+        assert!(
+            !PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoPermission))
+                .requires_signed_origin()
+        );
+    }
+
+    #[test]
+    fn test_in_use_classification() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InUse));
+        assert_eq!(error.user_action(), Some(UserAction::ChooseDifferentId));
+        assert_eq!(
+            error.to_string(),
+            "fungibles error: the asset ID is already taken"
+        );
+    }
+
+    #[test]
+    fn test_unknown_asset_classification() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::Unknown));
+        // `Unknown` of the assets pallet.
+        let module_error = crate::ModuleError {
+            index: crate::ASSETS_PALLET_INDEX,
+            error: 3,
+        };
+        assert_eq!(crate::refine_module_error(module_error), error);
+        assert_eq!(error.user_action(), Some(UserAction::CheckAssetId));
+        assert_eq!(
+            error.to_string(),
+            "fungibles error: the given asset ID is unknown"
+        );
+        assert!(error.is_client_safe());
+        assert_eq!(error.http_status(), 404);
+    }
+
+    #[test]
+    fn test_cannot_increase_allowance_classification() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(
+            FungiblesError::CannotIncreaseAllowance,
+        ));
+        let overflow = PopApiError::Arithmetic(crate::ArithmeticError::Overflow);
+        assert_eq!(crate::refine_approval_error(overflow), error);
+        assert_eq!(error.user_action(), Some(UserAction::ReduceAmount));
+        assert_eq!(
+            error.to_string(),
+            "fungibles error: the allowance cannot be increased any further"
+        );
+        assert!(error.is_client_safe());
+        assert_eq!(error.http_status(), 400);
+    }
+
+    #[test]
+    fn test_too_many_consumers_classification() {
+        let error = PopApiError::TooManyConsumers;
+        assert_eq!(error.to_string(), "account has too many consumers");
+        assert_eq!(error.user_action(), Some(UserAction::RemoveDependencies));
+        assert!(error.is_reference_count_error());
+        assert!(!error.is_account_error());
+        assert_eq!(crate::try_decode_from_u32(u32::from(error)).unwrap(), error);
+    }
+
+    #[test]
+    fn test_reference_count_errors() {
+        let reference_count_errors: Vec<_> = PopApiError::all_variants()
+            .into_iter()
+            .filter(|error| error.is_reference_count_error())
+            .collect();
+        assert_eq!(
+            reference_count_errors,
+            [
+                PopApiError::ConsumerRemaining,
+                PopApiError::NoProviders,
+                PopApiError::TooManyConsumers
+            ]
+        );
+    }
+
+    #[test]
+    fn test_origin() {
+        assert_eq!(PopApiError::Other(0).origin(), ErrorOrigin::Either);
+        assert_eq!(
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoPermission)).origin(),
+            ErrorOrigin::Either
+        );
+        assert_eq!(PopApiError::ConsumerRemaining.origin(), ErrorOrigin::Chain);
+        assert_eq!(
+            PopApiError::Module(crate::ModuleError { index: 1, error: 2 }).origin(),
+            ErrorOrigin::Chain
+        );
+        // `Contract` is reserved.
+        assert!(PopApiError::all_variants()
+            .iter()
+            .all(|error| error.origin() != ErrorOrigin::Contract));
+    }
+
+    #[test]
+    fn test_missing_role_is_opaque() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoPermission));
+        assert_eq!(error.missing_role(), None);
+        assert_eq!(u32::from(error).to_le_bytes(), [14, 0, 7, 0]);
+        assert!(PopApiError::all_variants()
+            .iter()
+            .all(|error| error.missing_role().is_none()));
+    }
+
+    #[test]
+    fn test_is_client_safe() {
+        let internal: Vec<_> = PopApiError::all_variants()
+            .into_iter()
+            .filter(|error| !error.is_client_safe())
+            .collect();
+        assert_eq!(
+            internal,
+            [
+                PopApiError::Corruption,
+                PopApiError::Unavailable,
+                PopApiError::Unspecified {
+                    dispatch_error_index: 3,
+                    error_index: 2,
+                    error: 1
+                }
+            ]
+        );
+        // Every error with a user action can be shown to the user.
+        assert!(PopApiError::all_variants()
+            .iter()
+            .filter(|error| error.user_action().is_some())
+            .all(PopApiError::is_client_safe));
+        assert!(
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance))
+                .is_client_safe()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// The encoding of `PopApiError` implemented on `const_status_code` and
+// `fast_decode` rather than derived, so that the wire format of status codes is
+// defined by this crate alone and can't shift with a new major version of
+// `parity_scale_codec`. Only the errors of invalid input are taken from the
+// derived `Decode`, which is kept for embedding errors in other types. The
+// tests assert that both agree byte for byte and error for error.
+use crate::{const_status_code, fast_decode, PopApiError};
+use parity_scale_codec::{Decode, Error, Input, Output};
+
+pub(crate) fn encode_to<O: Output + ?Sized>(error: &PopApiError, dest: &mut O) {
+    let bytes = const_status_code(*error).to_le_bytes();
+    dest.write(&bytes[..encoded_len(&bytes)]);
+}
+
+// The length of the encoding starting with `bytes`, as far as it is known from
+// them: the variant index and, for `UseCase`, the use case index. Unknown
+// indices end the encoding.
+fn encoded_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [] => 1,
+        [1 | 2 | 4 | 5 | 6 | 10..=13, ..] => 1,
+        [0 | 7 | 8 | 9, ..] | [14] => 2,
+        [3, ..] | [14, 0, ..] => 3,
+        [14, 1, ..] | [15, ..] => 4,
+        _ => bytes.len(),
+    }
+}
+
+// Decodes an error, failing with the same errors as the derived `Decode`. No
+// more bytes are read than the derived `Decode` reads.
+pub(crate) fn decode<I: Input>(input: &mut I) -> Result<PopApiError, Error> {
+    let mut bytes = [0; 4];
+    let mut len = 0;
+    while len < encoded_len(&bytes[..len]) {
+        match input.read_byte() {
+            Ok(byte) => bytes[len] = byte,
+            Err(_) => return Err(derived_error(&bytes[..len])),
+        }
+        len += 1;
+    }
+    fast_decode(u32::from_le_bytes(bytes)).ok_or_else(|| derived_error(&bytes[..len]))
+}
+
+// The error of the derived `Decode` for bytes it rejects, e.g. the bytes read
+// before the input ended. Panics if the derive accepts the bytes after all:
+// the layout of `fast_decode` is then wrong, which must not pass unnoticed.
+fn derived_error(bytes: &[u8]) -> Error {
+    match PopApiError::decode(&mut &bytes[..]) {
+        Ok(_) => unreachable!("`fast_decode` rejects an error the derived `Decode` accepts"),
+        Err(error) => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::all_valid_codes;
+    use parity_scale_codec::Encode;
+
+    // Decodes with both implementations, returning the results and the number
+    // of bytes left.
+    fn decode_both(bytes: &[u8]) -> [(Result<PopApiError, Error>, usize); 2] {
+        let mut compat = bytes;
+        let mut derived = bytes;
+        [
+            (decode(&mut compat), compat.len()),
+            (PopApiError::decode(&mut derived), derived.len()),
+        ]
+    }
+
+    #[test]
+    fn test_encoding_matches_derive() {
+        for code in all_valid_codes() {
+            let error = PopApiError::try_from(code).unwrap();
+            let mut encoded = Vec::new();
+            encode_to(&error, &mut encoded);
+            assert_eq!(encoded, error.encode(), "{error:?}");
+        }
+    }
+
+    #[test]
+    fn test_decoding_matches_derive() {
+        const SAMPLES: [u8; 4] = [0, 1, 0x7f, u8::MAX];
+        for a in 0..=u8::MAX {
+            for b in 0..=16 {
+                for c in (0..=12).chain(SAMPLES) {
+                    for d in SAMPLES {
+                        let bytes = [a, b, c, d];
+                        let [compat, derived] = decode_both(&bytes);
+                        assert_eq!(compat, derived, "{bytes:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    // Input ending within an error fails the same way, for every field.
+    #[test]
+    fn test_truncated_matches_derive() {
+        for code in all_valid_codes() {
+            let encoded = PopApiError::try_from(code).unwrap().encode();
+            for len in 0..encoded.len() {
+                let [compat, derived] = decode_both(&encoded[..len]);
+                assert!(compat.0.is_err());
+                assert_eq!(compat, derived, "{:?}", &encoded[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let [(error, _), _] = decode_both(&[7, 10]);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "Could not decode `PopApiError::Token.0`:\n\tCould not decode `TokenError`, \
+             variant doesn't exist\n"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// The numeric protocol: the discriminants of the top-level variants and the
+// status codes of the nested variants, for downstream code and other
+// languages. All of them are computed from the encoding by
+// `const_status_code`.
+use crate::{
+    const_status_code, ArithmeticError, FungiblesError, ModuleError, PopApiError, TokenError,
+    TransactionalError, UseCaseError,
+};
+use PopApiError::*;
+
+const fn discriminant(error: PopApiError) -> u8 {
+    const_status_code(error).to_le_bytes()[0]
+}
+
+// Discriminants of the top-level variants.
+pub const OTHER: u8 = discriminant(Other(0));
+pub const CANNOT_LOOKUP: u8 = discriminant(CannotLookup);
+pub const BAD_ORIGIN: u8 = discriminant(BadOrigin);
+pub const MODULE: u8 = discriminant(Module(ModuleError { index: 0, error: 0 }));
+pub const CONSUMER_REMAINING: u8 = discriminant(ConsumerRemaining);
+pub const NO_PROVIDERS: u8 = discriminant(NoProviders);
+pub const TOO_MANY_CONSUMERS: u8 = discriminant(TooManyConsumers);
+pub const TOKEN: u8 = discriminant(Token(TokenError::FundsUnavailable));
+pub const ARITHMETIC: u8 = discriminant(Arithmetic(ArithmeticError::Underflow));
+pub const TRANSACTIONAL: u8 = discriminant(Transactional(TransactionalError::LimitReached));
+pub const EXHAUSTED: u8 = discriminant(Exhausted);
+pub const CORRUPTION: u8 = discriminant(Corruption);
+pub const UNAVAILABLE: u8 = discriminant(Unavailable);
+pub const ROOT_NOT_ALLOWED: u8 = discriminant(RootNotAllowed);
+pub const USE_CASE: u8 = discriminant(UseCase(UseCaseError::Fungibles(
+    FungiblesError::AssetNotLive,
+)));
+pub const UNSPECIFIED: u8 = discriminant(Unspecified {
+    dispatch_error_index: 0,
+    error_index: 0,
+    error: 0,
+});
+
+// Status codes of the token errors.
+pub const TOKEN_FUNDS_UNAVAILABLE: u32 = const_status_code(Token(TokenError::FundsUnavailable));
+pub const TOKEN_ONLY_PROVIDER: u32 = const_status_code(Token(TokenError::OnlyProvider));
+pub const TOKEN_BELOW_MINIMUM: u32 = const_status_code(Token(TokenError::BelowMinimum));
+pub const TOKEN_CANNOT_CREATE: u32 = const_status_code(Token(TokenError::CannotCreate));
+pub const TOKEN_UNKNOWN_ASSET: u32 = const_status_code(Token(TokenError::UnknownAsset));
+pub const TOKEN_FROZEN: u32 = const_status_code(Token(TokenError::Frozen));
+pub const TOKEN_UNSUPPORTED: u32 = const_status_code(Token(TokenError::Unsupported));
+pub const TOKEN_CANNOT_CREATE_HOLD: u32 = const_status_code(Token(TokenError::CannotCreateHold));
+pub const TOKEN_NOT_EXPENDABLE: u32 = const_status_code(Token(TokenError::NotExpendable));
+pub const TOKEN_BLOCKED: u32 = const_status_code(Token(TokenError::Blocked));
+
+// Status codes of the arithmetic errors.
+pub const ARITHMETIC_UNDERFLOW: u32 = const_status_code(Arithmetic(ArithmeticError::Underflow));
+pub const ARITHMETIC_OVERFLOW: u32 = const_status_code(Arithmetic(ArithmeticError::Overflow));
+pub const ARITHMETIC_DIVISION_BY_ZERO: u32 =
+    const_status_code(Arithmetic(ArithmeticError::DivisionByZero));
+
+// Status codes of the transactional errors.
+pub const TRANSACTIONAL_LIMIT_REACHED: u32 =
+    const_status_code(Transactional(TransactionalError::LimitReached));
+pub const TRANSACTIONAL_NO_LAYER: u32 =
+    const_status_code(Transactional(TransactionalError::NoLayer));
+
+// Status codes of the fungibles use case errors.
+pub const FUNGIBLES_ASSET_NOT_LIVE: u32 = const_status_code(UseCase(UseCaseError::Fungibles(
+    FungiblesError::AssetNotLive,
+)));
+pub const FUNGIBLES_BELOW_MINIMUM: u32 = const_status_code(UseCase(UseCaseError::Fungibles(
+    FungiblesError::BelowMinimum,
+)));
+pub const FUNGIBLES_INSUFFICIENT_ALLOWANCE: u32 = const_status_code(UseCase(
+    UseCaseError::Fungibles(FungiblesError::InsufficientAllowance),
+));
+pub const FUNGIBLES_INSUFFICIENT_BALANCE: u32 = const_status_code(UseCase(
+    UseCaseError::Fungibles(FungiblesError::InsufficientBalance),
+));
+pub const FUNGIBLES_IN_USE: u32 =
+    const_status_code(UseCase(UseCaseError::Fungibles(FungiblesError::InUse)));
+pub const FUNGIBLES_MIN_BALANCE_ZERO: u32 = const_status_code(UseCase(UseCaseError::Fungibles(
+    FungiblesError::MinBalanceZero,
+)));
+pub const FUNGIBLES_NO_ACCOUNT: u32 =
+    const_status_code(UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount)));
+pub const FUNGIBLES_NO_PERMISSION: u32 = const_status_code(UseCase(UseCaseError::Fungibles(
+    FungiblesError::NoPermission,
+)));
+pub const FUNGIBLES_UNKNOWN: u32 =
+    const_status_code(UseCase(UseCaseError::Fungibles(FungiblesError::Unknown)));
+pub const FUNGIBLES_CANNOT_INCREASE_ALLOWANCE: u32 = const_status_code(UseCase(
+    UseCaseError::Fungibles(FungiblesError::CannotIncreaseAllowance),
+));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_scale_codec::Encode;
+
+    #[test]
+    fn test_discriminants() {
+        let discriminants = [
+            (OTHER, Other(0)),
+            (CANNOT_LOOKUP, CannotLookup),
+            (BAD_ORIGIN, BadOrigin),
+            (MODULE, Module(ModuleError { index: 0, error: 0 })),
+            (CONSUMER_REMAINING, ConsumerRemaining),
+            (NO_PROVIDERS, NoProviders),
+            (TOO_MANY_CONSUMERS, TooManyConsumers),
+            (TOKEN, Token(TokenError::FundsUnavailable)),
+            (ARITHMETIC, Arithmetic(ArithmeticError::Underflow)),
+            (
+                TRANSACTIONAL,
+                Transactional(TransactionalError::LimitReached),
+            ),
+            (EXHAUSTED, Exhausted),
+            (CORRUPTION, Corruption),
+            (UNAVAILABLE, Unavailable),
+            (ROOT_NOT_ALLOWED, RootNotAllowed),
+            (
+                USE_CASE,
+                UseCase(UseCaseError::Fungibles(FungiblesError::AssetNotLive)),
+            ),
+            (
+                UNSPECIFIED,
+                Unspecified {
+                    dispatch_error_index: 0,
+                    error_index: 0,
+                    error: 0,
+                },
+            ),
+        ];
+        for (discriminant, error) in discriminants {
+            assert_eq!(discriminant, error.encode()[0], "{error:?}");
+        }
+        assert_eq!(USE_CASE, 14);
+        assert_eq!(UNSPECIFIED, crate::max_known_variant());
+    }
+
+    #[test]
+    fn test_nested_codes() {
+        let codes = [
+            (TOKEN_FUNDS_UNAVAILABLE, Token(TokenError::FundsUnavailable)),
+            (TOKEN_ONLY_PROVIDER, Token(TokenError::OnlyProvider)),
+            (TOKEN_BELOW_MINIMUM, Token(TokenError::BelowMinimum)),
+            (TOKEN_CANNOT_CREATE, Token(TokenError::CannotCreate)),
+            (TOKEN_UNKNOWN_ASSET, Token(TokenError::UnknownAsset)),
+            (TOKEN_FROZEN, Token(TokenError::Frozen)),
+            (TOKEN_UNSUPPORTED, Token(TokenError::Unsupported)),
+            (
+                TOKEN_CANNOT_CREATE_HOLD,
+                Token(TokenError::CannotCreateHold),
+            ),
+            (TOKEN_NOT_EXPENDABLE, Token(TokenError::NotExpendable)),
+            (TOKEN_BLOCKED, Token(TokenError::Blocked)),
+            (ARITHMETIC_UNDERFLOW, Arithmetic(ArithmeticError::Underflow)),
+            (ARITHMETIC_OVERFLOW, Arithmetic(ArithmeticError::Overflow)),
+            (
+                ARITHMETIC_DIVISION_BY_ZERO,
+                Arithmetic(ArithmeticError::DivisionByZero),
+            ),
+            (
+                TRANSACTIONAL_LIMIT_REACHED,
+                Transactional(TransactionalError::LimitReached),
+            ),
+            (
+                TRANSACTIONAL_NO_LAYER,
+                Transactional(TransactionalError::NoLayer),
+            ),
+            (
+                FUNGIBLES_ASSET_NOT_LIVE,
+                UseCase(UseCaseError::Fungibles(FungiblesError::AssetNotLive)),
+            ),
+            (
+                FUNGIBLES_BELOW_MINIMUM,
+                UseCase(UseCaseError::Fungibles(FungiblesError::BelowMinimum)),
+            ),
+            (
+                FUNGIBLES_INSUFFICIENT_ALLOWANCE,
+                UseCase(UseCaseError::Fungibles(
+                    FungiblesError::InsufficientAllowance,
+                )),
+            ),
+            (
+                FUNGIBLES_INSUFFICIENT_BALANCE,
+                UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+            ),
+            (
+                FUNGIBLES_IN_USE,
+                UseCase(UseCaseError::Fungibles(FungiblesError::InUse)),
+            ),
+            (
+                FUNGIBLES_MIN_BALANCE_ZERO,
+                UseCase(UseCaseError::Fungibles(FungiblesError::MinBalanceZero)),
+            ),
+            (
+                FUNGIBLES_NO_ACCOUNT,
+                UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount)),
+            ),
+            (
+                FUNGIBLES_NO_PERMISSION,
+                UseCase(UseCaseError::Fungibles(FungiblesError::NoPermission)),
+            ),
+            (
+                FUNGIBLES_UNKNOWN,
+                UseCase(UseCaseError::Fungibles(FungiblesError::Unknown)),
+            ),
+            (
+                FUNGIBLES_CANNOT_INCREASE_ALLOWANCE,
+                UseCase(UseCaseError::Fungibles(
+                    FungiblesError::CannotIncreaseAllowance,
+                )),
+            ),
+        ];
+        for (code, error) in codes {
+            assert_eq!(code, u32::from(error), "{error:?}");
+        }
+        assert_eq!(FUNGIBLES_INSUFFICIENT_BALANCE.to_le_bytes(), [14, 0, 3, 0]);
+    }
+}
@@ -0,0 +1,69 @@
+// Compact encoding made concrete. Compact integers use the lowest two bits of
+// their first byte as mode: values up to 63 take one byte, up to 16383 two
+// bytes, up to 2^30 - 1 four bytes, anything larger a length byte followed by
+// the value's bytes. Compact thus pays off for small values of wide types and
+// costs for large values or narrow types, as the tests below pin down.
+use parity_scale_codec::{Encode, EncodeAsRef, HasCompact};
+
+// The plain and the compact encoding of a value.
+pub fn compare_compact<T: Encode + HasCompact>(value: T) -> (Vec<u8>, Vec<u8>) {
+    let compact = <<T as HasCompact>::Type as EncodeAsRef<'_, T>>::RefType::from(&value).encode();
+    (value.encode(), compact)
+}
+
+// The bytes saved by compact encoding the value, negative if compact encoding
+// is longer.
+pub fn compact_savings<T: Encode + HasCompact>(value: T) -> isize {
+    let (plain, compact) = compare_compact(value);
+    plain.len() as isize - compact.len() as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_compact() {
+        assert_eq!(compare_compact(63u32), (vec![63, 0, 0, 0], vec![63 << 2]));
+        assert_eq!(compare_compact(64u32), (vec![64, 0, 0, 0], vec![1, 1]));
+    }
+
+    #[test]
+    fn test_u8_boundaries() {
+        // A `u8` never benefits, above 63 compact needs two bytes.
+        assert_eq!(compact_savings(0u8), 0);
+        assert_eq!(compact_savings(63u8), 0);
+        assert_eq!(compact_savings(64u8), -1);
+        assert_eq!(compact_savings(u8::MAX), -1);
+    }
+
+    #[test]
+    fn test_u32_boundaries() {
+        let lens = [0u32, 63, 64, 16383, 16384, (1 << 30) - 1, 1 << 30, u32::MAX]
+            .map(|value| compare_compact(value).1.len());
+        assert_eq!(lens, [1, 1, 2, 2, 4, 4, 5, 5]);
+        assert_eq!(compact_savings(63u32), 3);
+        assert_eq!(compact_savings(16383u32), 2);
+        assert_eq!(compact_savings(16384u32), 0);
+        assert_eq!(compact_savings(1u32 << 30), -1);
+    }
+
+    #[test]
+    fn test_u128_boundaries() {
+        let lens = [
+            0u128,
+            63,
+            64,
+            16383,
+            16384,
+            1 << 30,
+            u64::MAX as u128,
+            u128::MAX,
+        ]
+        .map(|value| compare_compact(value).1.len());
+        assert_eq!(lens, [1, 1, 2, 2, 4, 5, 9, 17]);
+        assert_eq!(compact_savings(63u128), 15);
+        assert_eq!(compact_savings(1u128 << 30), 11);
+        assert_eq!(compact_savings(u128::MAX), -1);
+    }
+}
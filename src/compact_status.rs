@@ -0,0 +1,120 @@
+// A two byte representation of errors for events and storage, where the four
+// bytes of a status code are too many. This is not the wire format of the
+// chain extension, contracts always receive the `u32` status code.
+use crate::{try_decode_from_u32, DecodeError, PopApiError};
+use parity_scale_codec::Encode;
+
+// The first two bytes of the status code as little endian `u16`: the low byte
+// holds the variant index, the high byte its single byte payload, if any. The
+// unit variants, `Other` and the token, arithmetic and transactional errors
+// fit, while `Module`, `UseCase` and `Unspecified` need more than two bytes.
+// Fitting is decided by the length of the encoding, not the value: e.g.
+// `Module` with a zero payload has a status code below `u16::MAX` but still
+// doesn't fit. Every error that fits has its own value, as the status codes are
+// unique.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CompactStatus(u16);
+
+impl CompactStatus {
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+// Returns the error back if it doesn't fit.
+impl TryFrom<PopApiError> for CompactStatus {
+    type Error = PopApiError;
+
+    fn try_from(error: PopApiError) -> Result<Self, Self::Error> {
+        if error.encoded_size() > 2 {
+            return Err(error);
+        }
+        Ok(CompactStatus(u32::from(error) as u16))
+    }
+}
+
+impl From<CompactStatus> for PopApiError {
+    fn from(status: CompactStatus) -> Self {
+        try_decode_from_u32(status.0.into()).expect("compact status is created from an error")
+    }
+}
+
+impl TryFrom<u16> for CompactStatus {
+    type Error = DecodeError;
+
+    // Only values of errors that fit are accepted, e.g. not the value of the
+    // status code of `UseCase(Fungibles(AssetNotLive))`.
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        let error = try_decode_from_u32(value.into())?;
+        CompactStatus::try_from(error).map_err(|_| DecodeError {
+            position: 0,
+            error: "Error doesn't fit a compact status".into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError, ModuleError, TokenError, UseCaseError};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_classification() {
+        let mut values = HashSet::new();
+        for error in PopApiError::all_variants() {
+            let fits = !matches!(
+                error,
+                PopApiError::Module(_) | PopApiError::UseCase(_) | PopApiError::Unspecified { .. }
+            );
+            match CompactStatus::try_from(error) {
+                Ok(status) => {
+                    assert!(fits, "{error:?} shouldn't fit");
+                    assert!(values.insert(status), "{error:?} collides");
+                    assert_eq!(PopApiError::from(status), error);
+                    assert_eq!(CompactStatus::try_from(status.get()), Ok(status));
+                }
+                Err(rejected) => {
+                    assert!(!fits, "{error:?} should fit");
+                    assert_eq!(rejected, error);
+                }
+            }
+        }
+        // Every unit and single byte payload variant, by the length the
+        // conversion decides by.
+        let fitting = PopApiError::all_variants()
+            .iter()
+            .filter(|error| error.encoded_size() <= 2)
+            .count();
+        assert_eq!(values.len(), fitting);
+    }
+
+    #[test]
+    fn test_bit_layout() {
+        let status = CompactStatus::try_from(PopApiError::Token(TokenError::Frozen)).unwrap();
+        assert_eq!(status.get().to_le_bytes(), [7, 5]);
+        let status = CompactStatus::try_from(PopApiError::Other(u8::MAX)).unwrap();
+        assert_eq!(status.get().to_le_bytes(), [0, u8::MAX]);
+    }
+
+    #[test]
+    fn test_rejected_errors() {
+        let rejected = [
+            PopApiError::Module(ModuleError { index: 0, error: 0 }),
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::AssetNotLive)),
+            PopApiError::Unspecified {
+                dispatch_error_index: 0,
+                error_index: 0,
+                error: 0,
+            },
+        ];
+        for error in rejected {
+            assert_eq!(CompactStatus::try_from(error), Err(error));
+            // Even though the status code of all of them is below `u16::MAX`.
+            assert!(CompactStatus::try_from(u32::from(error) as u16).is_err());
+        }
+        // Values that aren't the first two bytes of an error.
+        assert!(CompactStatus::try_from(u16::from_le_bytes([16, 0])).is_err());
+        assert!(CompactStatus::try_from(u16::from_le_bytes([2, 1])).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+// Conformance checks for `Converter` implementations, so that runtimes with
+// their own mapping can verify they uphold the invariants of this crate.
+use crate::{
+    fungibles_from_pallet_error, Converter, PopApiError, ASSETS_PALLET_INDEX,
+    BALANCES_PALLET_INDEX, SYSTEM_PALLET_INDEX,
+};
+use sp_runtime::{DispatchError, ModuleError};
+
+// What a converter claims to support.
+#[derive(Debug, Default, Clone)]
+pub struct ConverterClaims {
+    // Pallet errors, as (pallet index, error index), that the converter claims
+    // to convert into a specific error rather than `Module` or `Unspecified`.
+    pub mapped_errors: Vec<(u8, u8)>,
+}
+
+impl ConverterClaims {
+    // The claims of `DefaultConverter`.
+    pub fn default_converter() -> Self {
+        let mut mapped_errors = vec![(SYSTEM_PALLET_INDEX, 4), (SYSTEM_PALLET_INDEX, 5)];
+        for pallet_index in [ASSETS_PALLET_INDEX, BALANCES_PALLET_INDEX] {
+            mapped_errors.extend(
+                (0..=u8::MAX)
+                    .filter(|error_index| {
+                        fungibles_from_pallet_error(pallet_index, *error_index).is_some()
+                    })
+                    .map(|error_index| (pallet_index, error_index)),
+            );
+        }
+        Self { mapped_errors }
+    }
+}
+
+// An invariant broken by a converter.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Violation {
+    // A pallet error claimed to be mapped is converted into a `Module` or
+    // `Unspecified` error.
+    Unmapped {
+        pallet_index: u8,
+        error_index: u8,
+        converted: PopApiError,
+    },
+    // Converting the same error twice results in different errors.
+    NonDeterministic {
+        error: DispatchError,
+        first: PopApiError,
+        second: PopApiError,
+    },
+}
+
+fn module_error(pallet_index: u8, error_index: u8) -> DispatchError {
+    DispatchError::Module(ModuleError {
+        index: pallet_index,
+        error: [error_index, 0, 0, 0],
+        message: None,
+    })
+}
+
+// Errors every converter is checked against: every `DispatchError` variant, the
+// claimed pallet errors and a sample of other module errors.
+fn inputs(claims: &ConverterClaims) -> Vec<DispatchError> {
+    use sp_runtime::{ArithmeticError as A, TokenError as T, TransactionalError as Tx};
+    let mut errors = vec![
+        DispatchError::Other(""),
+        DispatchError::CannotLookup,
+        DispatchError::BadOrigin,
+        DispatchError::ConsumerRemaining,
+        DispatchError::NoProviders,
+        DispatchError::TooManyConsumers,
+        DispatchError::Token(T::FundsUnavailable),
+        DispatchError::Arithmetic(A::Overflow),
+        DispatchError::Transactional(Tx::LimitReached),
+        DispatchError::Exhausted,
+        DispatchError::Corruption,
+        DispatchError::Unavailable,
+        DispatchError::RootNotAllowed,
+    ];
+    errors.extend(
+        claims
+            .mapped_errors
+            .iter()
+            .map(|(pallet_index, error_index)| module_error(*pallet_index, *error_index)),
+    );
+    errors.extend(
+        (0..=u8::MAX)
+            .step_by(17)
+            .map(|index| module_error(index, index)),
+    );
+    errors
+}
+
+// Checks the converter against its claims, returning every violation found.
+pub fn check<C: Converter>(converter: &C, claims: &ConverterClaims) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for error in inputs(claims) {
+        let first = converter.convert(error);
+        let second = converter.convert(error);
+        if first != second {
+            violations.push(Violation::NonDeterministic {
+                error,
+                first,
+                second,
+            });
+        }
+    }
+    for (pallet_index, error_index) in &claims.mapped_errors {
+        let converted = converter.convert(module_error(*pallet_index, *error_index));
+        if matches!(
+            converted,
+            PopApiError::Module(_) | PopApiError::Unspecified { .. }
+        ) {
+            violations.push(Violation::Unmapped {
+                pallet_index: *pallet_index,
+                error_index: *error_index,
+                converted,
+            });
+        }
+    }
+    violations
+}
+
+// Panics listing the violations if the converter doesn't conform to its
+// claims.
+#[macro_export]
+macro_rules! assert_conformant {
+    ($converter:expr, $claims:expr) => {
+        let violations = $crate::conformance::check(&$converter, &$claims);
+        assert!(
+            violations.is_empty(),
+            "converter is not conformant: {violations:#?}"
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultConverter, ModuleError as PopModuleError};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_default_converter_is_conformant() {
+        assert_conformant!(DefaultConverter, ConverterClaims::default_converter());
+    }
+
+    // Doesn't map anything and alternates the result of every other call.
+    #[derive(Default)]
+    struct BrokenConverter {
+        calls: Cell<u8>,
+    }
+
+    impl Converter for BrokenConverter {
+        fn convert(&self, error: DispatchError) -> PopApiError {
+            self.calls.set(self.calls.get().wrapping_add(1));
+            match error {
+                DispatchError::BadOrigin if self.calls.get().is_multiple_of(2) => {
+                    PopApiError::Other(0)
+                }
+                DispatchError::Module(error) => PopApiError::Module(PopModuleError {
+                    index: error.index,
+                    error: error.error[0],
+                }),
+                error => error.into(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_broken_converter_violations() {
+        let claims = ConverterClaims {
+            mapped_errors: vec![(ASSETS_PALLET_INDEX, 0)],
+        };
+        let violations = check(&BrokenConverter::default(), &claims);
+        assert_eq!(
+            violations,
+            [
+                Violation::NonDeterministic {
+                    error: DispatchError::BadOrigin,
+                    first: PopApiError::BadOrigin,
+                    second: PopApiError::Other(0),
+                },
+                Violation::Unmapped {
+                    pallet_index: ASSETS_PALLET_INDEX,
+                    error_index: 0,
+                    converted: PopApiError::Module(PopModuleError {
+                        index: ASSETS_PALLET_INDEX,
+                        error: 0,
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "converter is not conformant")]
+    fn test_assert_conformant_panics() {
+        let claims = ConverterClaims {
+            mapped_errors: vec![(ASSETS_PALLET_INDEX, 0)],
+        };
+        assert_conformant!(BrokenConverter::default(), claims);
+    }
+}
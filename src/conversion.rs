@@ -0,0 +1,740 @@
+// Runtime side conversion of a `DispatchError` into a `PopApiError`. All
+// matches on polkadot sdk types are exhaustive on purpose: a new variant in an
+// sdk upgrade fails to compile here instead of silently falling through.
+use crate::{
+    decode_with_remainder, refine_approval_error, refine_module_error, ArithmeticError,
+    FungiblesError, ModuleError, PopApiError, TokenError, TransactionalError, UseCaseError,
+    ASSETS_PALLET_INDEX, BALANCES_PALLET_INDEX, SYSTEM_PALLET_INDEX,
+};
+use sp_runtime::DispatchError;
+
+// Index of the `ExtrinsicFailed` event within the events of `frame_system`.
+const EXTRINSIC_FAILED_EVENT_INDEX: u8 = 1;
+
+// Messages of `DispatchError::Other` that are known to the runtime. A message
+// is converted to `PopApiError::Other` with its position in this list plus one,
+// unknown messages are converted to `PopApiError::Other(0)`.
+const OTHER_ERRORS: [&str; 2] = ["DecodingFailed", "UnknownFunctionId"];
+
+impl From<DispatchError> for PopApiError {
+    fn from(error: DispatchError) -> Self {
+        use PopApiError::*;
+        match error {
+            DispatchError::Other(message) => Other(
+                OTHER_ERRORS
+                    .iter()
+                    .position(|known| *known == message)
+                    .map_or(0, |position| position as u8 + 1),
+            ),
+            DispatchError::CannotLookup => CannotLookup,
+            DispatchError::BadOrigin => BadOrigin,
+            DispatchError::Module(error) => refine_module_error(ModuleError {
+                index: error.index,
+                error: error.error[0],
+            }),
+            DispatchError::ConsumerRemaining => ConsumerRemaining,
+            DispatchError::NoProviders => NoProviders,
+            DispatchError::TooManyConsumers => TooManyConsumers,
+            DispatchError::Token(error) => Token(error.into()),
+            DispatchError::Arithmetic(error) => Arithmetic(error.into()),
+            DispatchError::Transactional(error) => Transactional(error.into()),
+            DispatchError::Exhausted => Exhausted,
+            DispatchError::Corruption => Corruption,
+            DispatchError::Unavailable => Unavailable,
+            DispatchError::RootNotAllowed => RootNotAllowed,
+        }
+    }
+}
+
+// Conversion of a `DispatchError` into a `PopApiError`. Runtimes with their own
+// mapping implement it, `conformance::check` verifies an implementation.
+pub trait Converter {
+    fn convert(&self, error: DispatchError) -> PopApiError;
+
+    // Converts an error of the approval path, i.e. `approve` and
+    // `increase_allowance`. Runtimes have to convert the errors of these calls
+    // with it rather than `convert`: only there an arithmetic overflow means
+    // that the allowance can't be increased, see `refine_approval_error`.
+    fn convert_approval(&self, error: DispatchError) -> PopApiError {
+        refine_approval_error(self.convert(error))
+    }
+
+    // Converts several errors, e.g. of a batch dispatch, in order. The converter
+    // is borrowed once for all errors.
+    fn convert_all<'a>(
+        &'a self,
+        errors: impl Iterator<Item = DispatchError> + 'a,
+    ) -> impl Iterator<Item = PopApiError> + 'a
+    where
+        Self: Sized,
+    {
+        errors.map(move |error| self.convert(error))
+    }
+
+    // Converts the errors into the caller provided buffer without allocating.
+    // Returns the number of converted errors, which is limited by the length of
+    // the buffer.
+    fn convert_into(&self, errors: &[DispatchError], buffer: &mut [PopApiError]) -> usize
+    where
+        Self: Sized,
+    {
+        let mut converted = 0;
+        for (error, slot) in errors.iter().zip(buffer.iter_mut()) {
+            *slot = self.convert(*error);
+            converted += 1;
+        }
+        converted
+    }
+}
+
+// The conversion of this crate, i.e. `From<DispatchError> for PopApiError`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultConverter;
+
+impl Converter for DefaultConverter {
+    fn convert(&self, error: DispatchError) -> PopApiError {
+        error.into()
+    }
+}
+
+impl From<sp_runtime::TokenError> for TokenError {
+    fn from(error: sp_runtime::TokenError) -> Self {
+        use sp_runtime::TokenError::*;
+        match error {
+            FundsUnavailable => TokenError::FundsUnavailable,
+            OnlyProvider => TokenError::OnlyProvider,
+            BelowMinimum => TokenError::BelowMinimum,
+            CannotCreate => TokenError::CannotCreate,
+            UnknownAsset => TokenError::UnknownAsset,
+            Frozen => TokenError::Frozen,
+            Unsupported => TokenError::Unsupported,
+            CannotCreateHold => TokenError::CannotCreateHold,
+            NotExpendable => TokenError::NotExpendable,
+            Blocked => TokenError::Blocked,
+        }
+    }
+}
+
+impl From<sp_runtime::ArithmeticError> for ArithmeticError {
+    fn from(error: sp_runtime::ArithmeticError) -> Self {
+        use sp_runtime::ArithmeticError::*;
+        match error {
+            Underflow => ArithmeticError::Underflow,
+            Overflow => ArithmeticError::Overflow,
+            DivisionByZero => ArithmeticError::DivisionByZero,
+        }
+    }
+}
+
+impl From<sp_runtime::TransactionalError> for TransactionalError {
+    fn from(error: sp_runtime::TransactionalError) -> Self {
+        use sp_runtime::TransactionalError::*;
+        match error {
+            LimitReached => TransactionalError::LimitReached,
+            NoLayer => TransactionalError::NoLayer,
+        }
+    }
+}
+
+// Converts the error back into a `DispatchError`. The conversion is lossy, but
+// converting the result again results in the same error:
+// - `Other` results in its registered message, an empty message if unknown.
+// - Use case errors result in the pallet error they are canonically mapped
+//   from, e.g. `InsufficientBalance` in the `BalanceLow` error of the assets
+//   pallet.
+// - `Unspecified` errors can't be expressed and result in an empty `Other`.
+impl From<PopApiError> for DispatchError {
+    fn from(error: PopApiError) -> Self {
+        use PopApiError::*;
+        let module = |index, error| {
+            DispatchError::Module(sp_runtime::ModuleError {
+                index,
+                error: [error, 0, 0, 0],
+                message: None,
+            })
+        };
+        match error {
+            Other(index) => DispatchError::Other(
+                usize::from(index)
+                    .checked_sub(1)
+                    .and_then(|position| OTHER_ERRORS.get(position))
+                    .unwrap_or(&""),
+            ),
+            CannotLookup => DispatchError::CannotLookup,
+            BadOrigin => DispatchError::BadOrigin,
+            Module(error) => module(error.index, error.error),
+            ConsumerRemaining => DispatchError::ConsumerRemaining,
+            NoProviders => DispatchError::NoProviders,
+            TooManyConsumers => DispatchError::TooManyConsumers,
+            Token(error) => DispatchError::Token(error.into()),
+            Arithmetic(error) => DispatchError::Arithmetic(error.into()),
+            Transactional(error) => DispatchError::Transactional(error.into()),
+            Exhausted => DispatchError::Exhausted,
+            Corruption => DispatchError::Corruption,
+            Unavailable => DispatchError::Unavailable,
+            RootNotAllowed => DispatchError::RootNotAllowed,
+            UseCase(UseCaseError::Fungibles(error)) => match pallet_error_from_fungibles(error) {
+                Some((pallet_index, error_index)) => module(pallet_index, error_index),
+                // Only raised by `Converter::convert_approval`.
+                None => DispatchError::Arithmetic(sp_runtime::ArithmeticError::Overflow),
+            },
+            UseCase(UseCaseError::Module(error)) => module(error.index, error.error),
+            Unspecified { .. } => DispatchError::Other(""),
+        }
+    }
+}
+
+impl From<TokenError> for sp_runtime::TokenError {
+    fn from(error: TokenError) -> Self {
+        use sp_runtime::TokenError::*;
+        match error {
+            TokenError::FundsUnavailable => FundsUnavailable,
+            TokenError::OnlyProvider => OnlyProvider,
+            TokenError::BelowMinimum => BelowMinimum,
+            TokenError::CannotCreate => CannotCreate,
+            TokenError::UnknownAsset => UnknownAsset,
+            TokenError::Frozen => Frozen,
+            TokenError::Unsupported => Unsupported,
+            TokenError::CannotCreateHold => CannotCreateHold,
+            TokenError::NotExpendable => NotExpendable,
+            TokenError::Blocked => Blocked,
+        }
+    }
+}
+
+impl From<ArithmeticError> for sp_runtime::ArithmeticError {
+    fn from(error: ArithmeticError) -> Self {
+        use sp_runtime::ArithmeticError::*;
+        match error {
+            ArithmeticError::Underflow => Underflow,
+            ArithmeticError::Overflow => Overflow,
+            ArithmeticError::DivisionByZero => DivisionByZero,
+        }
+    }
+}
+
+impl From<TransactionalError> for sp_runtime::TransactionalError {
+    fn from(error: TransactionalError) -> Self {
+        use sp_runtime::TransactionalError::*;
+        match error {
+            TransactionalError::LimitReached => LimitReached,
+            TransactionalError::NoLayer => NoLayer,
+        }
+    }
+}
+
+// Extracts the `DispatchError` out of an encoded `ExtrinsicFailed` event of
+// `frame_system`, i.e. the pallet index, event index and event fields. The
+// `DispatchInfo` following the error is ignored.
+pub fn extract_dispatch_error(event: &[u8]) -> Result<DispatchError, parity_scale_codec::Error> {
+    match event {
+        [SYSTEM_PALLET_INDEX, EXTRINSIC_FAILED_EVENT_INDEX, fields @ ..] => {
+            decode_with_remainder(fields).map(|(error, _dispatch_info)| error)
+        }
+        _ => Err("Event is not an `ExtrinsicFailed` event".into()),
+    }
+}
+
+// The pallet error a fungibles error is canonically mapped from, the inverse of
+// `fungibles_from_pallet_error`. `None` for the errors that are not mapped from
+// a pallet error.
+fn pallet_error_from_fungibles(error: FungiblesError) -> Option<(u8, u8)> {
+    use FungiblesError::*;
+    Some(match error {
+        AssetNotLive => (ASSETS_PALLET_INDEX, 16),
+        BelowMinimum => (BALANCES_PALLET_INDEX, 3),
+        InsufficientAllowance => (ASSETS_PALLET_INDEX, 10),
+        InsufficientBalance => (ASSETS_PALLET_INDEX, 0),
+        InUse => (ASSETS_PALLET_INDEX, 5),
+        MinBalanceZero => (ASSETS_PALLET_INDEX, 7),
+        NoAccount => (ASSETS_PALLET_INDEX, 1),
+        NoPermission => (ASSETS_PALLET_INDEX, 2),
+        Unknown => (ASSETS_PALLET_INDEX, 3),
+        CannotIncreaseAllowance => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fungibles_from_pallet_error;
+    use crate::fuzzing::arbitrary_dispatch_error;
+    use arbitrary::Unstructured;
+    use parity_scale_codec::Encode;
+    use proptest::prelude::*;
+
+    fn module(index: u8, error: u8) -> DispatchError {
+        DispatchError::Module(sp_runtime::ModuleError {
+            index,
+            error: [error, 0, 0, 0],
+            message: None,
+        })
+    }
+
+    fn fungibles(error: FungiblesError) -> PopApiError {
+        PopApiError::UseCase(UseCaseError::Fungibles(error))
+    }
+
+    // Index of the variant within the `DispatchError`. Intentionally without a
+    // wildcard so that a new `DispatchError` variant doesn't compile until it
+    // has been added to the matrix below.
+    fn dispatch_error_index(error: &DispatchError) -> u8 {
+        match error {
+            DispatchError::Other(_) => 0,
+            DispatchError::CannotLookup => 1,
+            DispatchError::BadOrigin => 2,
+            DispatchError::Module(_) => 3,
+            DispatchError::ConsumerRemaining => 4,
+            DispatchError::NoProviders => 5,
+            DispatchError::TooManyConsumers => 6,
+            DispatchError::Token(_) => 7,
+            DispatchError::Arithmetic(_) => 8,
+            DispatchError::Transactional(_) => 9,
+            DispatchError::Exhausted => 10,
+            DispatchError::Corruption => 11,
+            DispatchError::Unavailable => 12,
+            DispatchError::RootNotAllowed => 13,
+        }
+    }
+
+    // The authoritative specification of the conversion.
+    fn conversion_matrix() -> Vec<(DispatchError, PopApiError)> {
+        use sp_runtime::{ArithmeticError as A, TokenError as T, TransactionalError as Tx};
+        use FungiblesError::*;
+        vec![
+            (
+                DispatchError::Other("DecodingFailed"),
+                PopApiError::Other(1),
+            ),
+            (
+                DispatchError::Other("UnknownFunctionId"),
+                PopApiError::Other(2),
+            ),
+            (DispatchError::Other("Unregistered"), PopApiError::Other(0)),
+            (DispatchError::CannotLookup, PopApiError::CannotLookup),
+            (DispatchError::BadOrigin, PopApiError::BadOrigin),
+            // Mapped `pallet-assets` errors.
+            (
+                module(ASSETS_PALLET_INDEX, 0),
+                fungibles(FungiblesError::InsufficientBalance),
+            ),
+            (module(ASSETS_PALLET_INDEX, 1), fungibles(NoAccount)),
+            (module(ASSETS_PALLET_INDEX, 2), fungibles(NoPermission)),
+            (module(ASSETS_PALLET_INDEX, 3), fungibles(Unknown)),
+            (module(ASSETS_PALLET_INDEX, 5), fungibles(InUse)),
+            (module(ASSETS_PALLET_INDEX, 7), fungibles(MinBalanceZero)),
+            (
+                module(ASSETS_PALLET_INDEX, 10),
+                fungibles(InsufficientAllowance),
+            ),
+            (module(ASSETS_PALLET_INDEX, 16), fungibles(AssetNotLive)),
+            // Mapped `pallet-balances` errors.
+            (
+                module(BALANCES_PALLET_INDEX, 2),
+                fungibles(FungiblesError::InsufficientBalance),
+            ),
+            (module(BALANCES_PALLET_INDEX, 3), fungibles(BelowMinimum)),
+            (module(BALANCES_PALLET_INDEX, 6), fungibles(NoAccount)),
+            // Unmapped errors of known and unknown pallets.
+            (
+                module(ASSETS_PALLET_INDEX, 4),
+                PopApiError::Module(ModuleError {
+                    index: ASSETS_PALLET_INDEX,
+                    error: 4,
+                }),
+            ),
+            (
+                module(BALANCES_PALLET_INDEX, 0),
+                PopApiError::Module(ModuleError {
+                    index: BALANCES_PALLET_INDEX,
+                    error: 0,
+                }),
+            ),
+            (
+                module(1, 5),
+                PopApiError::Module(ModuleError { index: 1, error: 5 }),
+            ),
+            // `frame_system` errors.
+            (
+                module(SYSTEM_PALLET_INDEX, 4),
+                PopApiError::ConsumerRemaining,
+            ),
+            (module(SYSTEM_PALLET_INDEX, 5), PopApiError::BadOrigin),
+            (
+                module(SYSTEM_PALLET_INDEX, 0),
+                PopApiError::Module(ModuleError { index: 0, error: 0 }),
+            ),
+            (
+                module(u8::MAX, u8::MAX),
+                PopApiError::Module(ModuleError {
+                    index: u8::MAX,
+                    error: u8::MAX,
+                }),
+            ),
+            (
+                DispatchError::ConsumerRemaining,
+                PopApiError::ConsumerRemaining,
+            ),
+            (DispatchError::NoProviders, PopApiError::NoProviders),
+            (
+                DispatchError::TooManyConsumers,
+                PopApiError::TooManyConsumers,
+            ),
+            (
+                DispatchError::Token(T::FundsUnavailable),
+                PopApiError::Token(TokenError::FundsUnavailable),
+            ),
+            (
+                DispatchError::Token(T::OnlyProvider),
+                PopApiError::Token(TokenError::OnlyProvider),
+            ),
+            (
+                DispatchError::Token(T::BelowMinimum),
+                PopApiError::Token(TokenError::BelowMinimum),
+            ),
+            (
+                DispatchError::Token(T::CannotCreate),
+                PopApiError::Token(TokenError::CannotCreate),
+            ),
+            (
+                DispatchError::Token(T::UnknownAsset),
+                PopApiError::Token(TokenError::UnknownAsset),
+            ),
+            (
+                DispatchError::Token(T::Frozen),
+                PopApiError::Token(TokenError::Frozen),
+            ),
+            (
+                DispatchError::Token(T::Unsupported),
+                PopApiError::Token(TokenError::Unsupported),
+            ),
+            (
+                DispatchError::Token(T::CannotCreateHold),
+                PopApiError::Token(TokenError::CannotCreateHold),
+            ),
+            (
+                DispatchError::Token(T::NotExpendable),
+                PopApiError::Token(TokenError::NotExpendable),
+            ),
+            (
+                DispatchError::Token(T::Blocked),
+                PopApiError::Token(TokenError::Blocked),
+            ),
+            (
+                DispatchError::Arithmetic(A::Underflow),
+                PopApiError::Arithmetic(ArithmeticError::Underflow),
+            ),
+            (
+                DispatchError::Arithmetic(A::Overflow),
+                PopApiError::Arithmetic(ArithmeticError::Overflow),
+            ),
+            (
+                DispatchError::Arithmetic(A::DivisionByZero),
+                PopApiError::Arithmetic(ArithmeticError::DivisionByZero),
+            ),
+            (
+                DispatchError::Transactional(Tx::LimitReached),
+                PopApiError::Transactional(TransactionalError::LimitReached),
+            ),
+            (
+                DispatchError::Transactional(Tx::NoLayer),
+                PopApiError::Transactional(TransactionalError::NoLayer),
+            ),
+            (DispatchError::Exhausted, PopApiError::Exhausted),
+            (DispatchError::Corruption, PopApiError::Corruption),
+            (DispatchError::Unavailable, PopApiError::Unavailable),
+            (DispatchError::RootNotAllowed, PopApiError::RootNotAllowed),
+        ]
+    }
+
+    #[test]
+    fn test_dispatch_error_conversion_matrix() {
+        let matrix = conversion_matrix();
+        // Every `DispatchError` variant is part of the matrix.
+        for index in 0..=dispatch_error_index(&DispatchError::RootNotAllowed) {
+            assert!(
+                matrix
+                    .iter()
+                    .any(|(error, _)| dispatch_error_index(error) == index),
+                "DispatchError variant with index {index} is missing from the matrix"
+            );
+        }
+        let mismatches: Vec<String> = matrix
+            .into_iter()
+            .filter_map(|(error, expected)| {
+                let actual = PopApiError::from(error);
+                (actual != expected)
+                    .then(|| format!("{error:?} | expected: {expected:?} | actual: {actual:?}"))
+            })
+            .collect();
+        assert!(
+            mismatches.is_empty(),
+            "conversion mismatches:\n{}",
+            mismatches.join("\n")
+        );
+    }
+
+    // `Unspecified` holds the first three bytes of an encoded `DispatchError`:
+    // the variant index and up to two bytes of its payload. Every variant index
+    // fits, the known ones as well as any future one up to `u8::MAX`. Of the
+    // payloads only that of `Module` doesn't fit entirely, the last three bytes
+    // of its error are dropped. Pallets only use the first byte so far.
+    #[test]
+    fn test_unspecified_covers_dispatch_error_indices() {
+        for (error, _) in conversion_matrix() {
+            let encoded = error.encode();
+            let index = dispatch_error_index(&error);
+            assert_eq!(encoded[0], index, "{error:?}");
+            let byte = |position: usize| encoded.get(position).copied().unwrap_or(0);
+            let unspecified = PopApiError::Unspecified {
+                dispatch_error_index: index,
+                error_index: byte(1),
+                error: byte(2),
+            };
+            assert_eq!(
+                PopApiError::try_from(u32::from(unspecified)),
+                Ok(unspecified)
+            );
+            match error {
+                DispatchError::Module(_) => assert_eq!(encoded.len(), 6),
+                _ => assert!(encoded.len() <= 3, "{error:?} doesn't fit"),
+            }
+        }
+        for index in 0..=u8::MAX {
+            let unspecified = PopApiError::Unspecified {
+                dispatch_error_index: index,
+                error_index: 0,
+                error: 0,
+            };
+            assert_eq!(
+                PopApiError::try_from(u32::from(unspecified)),
+                Ok(unspecified)
+            );
+        }
+    }
+
+    #[test]
+    fn test_module_error_message_and_nested_bytes_are_ignored() {
+        let error = DispatchError::Module(sp_runtime::ModuleError {
+            index: ASSETS_PALLET_INDEX,
+            error: [0, 1, 2, 3],
+            message: Some("BalanceLow"),
+        });
+        assert_eq!(
+            PopApiError::from(error),
+            fungibles(FungiblesError::InsufficientBalance)
+        );
+    }
+
+    #[test]
+    fn test_every_fungibles_error_is_reachable() {
+        // Variants that are only produced contract side and therefore never by
+        // the runtime's mapping tables.
+        const CONTRACT_SIDE_ONLY: &[FungiblesError] = &[];
+        let mut reachable = Vec::new();
+        for pallet_index in 0..=u8::MAX {
+            for error_index in 0..=u8::MAX {
+                if let Some(error) = fungibles_from_pallet_error(pallet_index, error_index) {
+                    reachable.push(error);
+                }
+            }
+        }
+        // Errors of the approval path.
+        let overflow = DispatchError::Arithmetic(sp_runtime::ArithmeticError::Overflow);
+        if let PopApiError::UseCase(UseCaseError::Fungibles(error)) =
+            DefaultConverter.convert_approval(overflow)
+        {
+            reachable.push(error);
+        }
+        let unreachable: Vec<_> = FungiblesError::all()
+            .iter()
+            .filter(|error| !reachable.contains(error) && !CONTRACT_SIDE_ONLY.contains(error))
+            .collect();
+        assert!(
+            unreachable.is_empty(),
+            "fungibles errors not produced by any mapping: {unreachable:?}"
+        );
+    }
+
+    #[test]
+    fn test_assets_in_use_conversion() {
+        assert_eq!(
+            fungibles_from_pallet_error(ASSETS_PALLET_INDEX, 5),
+            Some(FungiblesError::InUse)
+        );
+        assert_eq!(
+            PopApiError::from(module(ASSETS_PALLET_INDEX, 5)),
+            fungibles(FungiblesError::InUse)
+        );
+    }
+
+    // An `ExtrinsicFailed` event encoded by this crate rather than captured on
+    // chain, so it only checks the extraction against the event layout as
+    // assumed here. The error is followed by the bytes of a `DispatchInfo` with
+    // a weight of (1_000_000, 1_000), `Normal` class and `Yes` pays fee.
+    fn extrinsic_failed(error: DispatchError) -> Vec<u8> {
+        use parity_scale_codec::Encode;
+        let mut event = vec![SYSTEM_PALLET_INDEX, EXTRINSIC_FAILED_EVENT_INDEX];
+        error.encode_to(&mut event);
+        event.extend([0x02, 0x09, 0x3d, 0x00, 0xa1, 0x0f, 0x00, 0x00]);
+        event
+    }
+
+    #[test]
+    fn test_extract_dispatch_error_from_encoded_events() {
+        let convert =
+            |error| PopApiError::from(extract_dispatch_error(&extrinsic_failed(error)).unwrap());
+        // The error of `pallet-assets` for a balance too low.
+        assert_eq!(
+            convert(module(ASSETS_PALLET_INDEX, 0)),
+            fungibles(FungiblesError::InsufficientBalance)
+        );
+        // The error of `pallet-balances` for an amount below the existential
+        // deposit.
+        assert_eq!(
+            convert(module(BALANCES_PALLET_INDEX, 3)),
+            fungibles(FungiblesError::BelowMinimum)
+        );
+        // An error of a pallet without mapping.
+        assert_eq!(
+            convert(module(40, 2)),
+            PopApiError::Module(ModuleError {
+                index: 40,
+                error: 2
+            })
+        );
+        assert_eq!(convert(DispatchError::BadOrigin), PopApiError::BadOrigin);
+    }
+
+    #[test]
+    fn test_extract_dispatch_error_rejects_other_events() {
+        // `ExtrinsicSuccess`.
+        assert!(extract_dispatch_error(&[SYSTEM_PALLET_INDEX, 0, 0]).is_err());
+        // Event of another pallet.
+        assert!(extract_dispatch_error(&[ASSETS_PALLET_INDEX, 1, 2]).is_err());
+        // Truncated event.
+        assert!(extract_dispatch_error(&[SYSTEM_PALLET_INDEX, 1]).is_err());
+    }
+
+    #[test]
+    fn test_canonical_pallet_errors_map_back() {
+        for error in FungiblesError::all() {
+            let Some((pallet_index, error_index)) = pallet_error_from_fungibles(*error) else {
+                continue;
+            };
+            assert_eq!(
+                fungibles_from_pallet_error(pallet_index, error_index),
+                Some(*error)
+            );
+        }
+    }
+
+    // DispatchError -> PopApiError -> DispatchError -> PopApiError: after the
+    // first conversion no further information may be lost.
+    fn assert_round_trip_is_idempotent(error: DispatchError) {
+        let first = PopApiError::from(error);
+        let dispatch_error = DispatchError::from(first);
+        let second = PopApiError::from(dispatch_error);
+        assert_eq!(second, first, "{error:?} loses information a second time");
+        assert_eq!(DispatchError::from(second), dispatch_error);
+    }
+
+    #[test]
+    fn test_round_trip_idempotence_over_matrix() {
+        for (error, _) in conversion_matrix() {
+            assert_round_trip_is_idempotent(error);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_idempotence(data in proptest::collection::vec(any::<u8>(), 0..16)) {
+            if let Ok(error) = arbitrary_dispatch_error(&mut Unstructured::new(&data)) {
+                assert_round_trip_is_idempotent(error);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lossy_round_trips() {
+        // An unregistered message is dropped the first time.
+        let error = PopApiError::from(DispatchError::Other("Unregistered"));
+        assert_eq!(DispatchError::from(error), DispatchError::Other(""));
+        // A registered message survives.
+        let error = PopApiError::from(DispatchError::Other("DecodingFailed"));
+        assert_eq!(
+            DispatchError::from(error),
+            DispatchError::Other("DecodingFailed")
+        );
+        // Nested bytes of an unmapped module error are dropped the first time.
+        let error = PopApiError::from(DispatchError::Module(sp_runtime::ModuleError {
+            index: 40,
+            error: [2, 1, 0, 0],
+            message: None,
+        }));
+        assert_eq!(DispatchError::from(error), module(40, 2));
+        // A use case error results in its canonical pallet error.
+        let error = PopApiError::from(module(BALANCES_PALLET_INDEX, 2));
+        assert_eq!(DispatchError::from(error), module(ASSETS_PALLET_INDEX, 0));
+        // `Unspecified` can't be expressed as a `DispatchError`.
+        let error = PopApiError::Unspecified {
+            dispatch_error_index: 14,
+            error_index: 1,
+            error: 0,
+        };
+        assert_eq!(DispatchError::from(error), DispatchError::Other(""));
+    }
+
+    #[test]
+    fn test_refine_system_errors() {
+        let system = |error| ModuleError {
+            index: SYSTEM_PALLET_INDEX,
+            error,
+        };
+        // `CallFiltered`
+        assert_eq!(refine_module_error(system(5)), PopApiError::BadOrigin);
+        // `NonZeroRefCount`
+        assert_eq!(
+            refine_module_error(system(4)),
+            PopApiError::ConsumerRemaining
+        );
+        // `InvalidSpecName` has no top-level counterpart.
+        assert_eq!(
+            refine_module_error(system(0)),
+            PopApiError::Module(system(0))
+        );
+    }
+
+    #[test]
+    fn test_convert_all_preserves_order() {
+        let errors = [
+            module(ASSETS_PALLET_INDEX, 0),
+            DispatchError::BadOrigin,
+            module(1, 2),
+            module(BALANCES_PALLET_INDEX, 3),
+            DispatchError::Other("UnknownFunctionId"),
+        ];
+        let expected = [
+            fungibles(FungiblesError::InsufficientBalance),
+            PopApiError::BadOrigin,
+            PopApiError::Module(ModuleError { index: 1, error: 2 }),
+            fungibles(FungiblesError::BelowMinimum),
+            PopApiError::Other(2),
+        ];
+        let converted: Vec<_> = DefaultConverter.convert_all(errors.into_iter()).collect();
+        assert_eq!(converted, expected);
+
+        let mut buffer = [PopApiError::Other(0); 5];
+        assert_eq!(DefaultConverter.convert_into(&errors, &mut buffer), 5);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn test_convert_into_is_limited_by_the_buffer() {
+        let errors = [DispatchError::BadOrigin, DispatchError::CannotLookup];
+        let mut buffer = [PopApiError::Other(0); 1];
+        assert_eq!(DefaultConverter.convert_into(&errors, &mut buffer), 1);
+        assert_eq!(buffer, [PopApiError::BadOrigin]);
+        assert_eq!(DefaultConverter.convert_into(&[], &mut buffer), 0);
+    }
+}
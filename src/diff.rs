@@ -0,0 +1,328 @@
+// Detects wire format drift between two versions of the error types, e.g. this
+// crate and a runtime's view of it, by comparing the variants of the enums
+// reachable from a set of root types. Types are matched structurally from the
+// roots on, so their module paths may differ between the registries.
+use core::fmt;
+use scale_info::{form::PortableForm, PortableRegistry, Type, TypeDef, Variant};
+use std::collections::BTreeSet;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum VariantChange {
+    /// A variant with a new name and index.
+    Added { ty: String, name: String, index: u8 },
+    /// A variant whose name and index are both gone.
+    Removed { ty: String, name: String, index: u8 },
+    /// A variant with a different name at the same index.
+    Renamed {
+        ty: String,
+        index: u8,
+        old: String,
+        new: String,
+    },
+    /// A variant with the same name at a different index.
+    Reindexed {
+        ty: String,
+        name: String,
+        old: u8,
+        new: u8,
+    },
+}
+
+impl fmt::Display for VariantChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VariantChange::*;
+        match self {
+            Added { ty, name, index } => write!(f, "added {ty}::{name} at index {index}"),
+            Removed { ty, name, index } => write!(f, "removed {ty}::{name} at index {index}"),
+            Renamed {
+                ty,
+                index,
+                old,
+                new,
+            } => {
+                write!(f, "renamed {ty}::{old} to {ty}::{new} at index {index}")
+            }
+            Reindexed { ty, name, old, new } => {
+                write!(f, "reindexed {ty}::{name} from {old} to {new}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RegistryDiff {
+    pub changes: Vec<VariantChange>,
+    // Roots that are missing from either registry and weren't compared.
+    pub missing_roots: Vec<String>,
+}
+
+impl RegistryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.missing_roots.is_empty()
+    }
+}
+
+// One line per change, for CI logs.
+impl fmt::Display for RegistryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no wire format changes");
+        }
+        for root in &self.missing_roots {
+            writeln!(f, "missing root {root}")?;
+        }
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+// Compares the types named by `roots`, either by their name or their full
+// path, and everything reachable from them.
+pub fn diff_registries(
+    old: &PortableRegistry,
+    new: &PortableRegistry,
+    roots: &[&str],
+) -> RegistryDiff {
+    let mut differ = Differ {
+        old,
+        new,
+        visited: BTreeSet::new(),
+        diff: RegistryDiff::default(),
+    };
+    for root in roots {
+        match (find(old, root), find(new, root)) {
+            (Some(old_id), Some(new_id)) => differ.diff(old_id, new_id),
+            _ => differ.diff.missing_roots.push(root.to_string()),
+        }
+    }
+    differ.diff
+}
+
+fn find(registry: &PortableRegistry, name: &str) -> Option<u32> {
+    registry
+        .types
+        .iter()
+        .find(|ty| ty.ty.path.ident().as_deref() == Some(name) || ty.ty.path.to_string() == name)
+        .map(|ty| ty.id)
+}
+
+struct Differ<'a> {
+    old: &'a PortableRegistry,
+    new: &'a PortableRegistry,
+    // Pairs of types already compared, which also stops recursive types.
+    visited: BTreeSet<(u32, u32)>,
+    diff: RegistryDiff,
+}
+
+impl Differ<'_> {
+    fn diff(&mut self, old_id: u32, new_id: u32) {
+        if !self.visited.insert((old_id, new_id)) {
+            return;
+        }
+        let old = resolve(self.old, old_id);
+        let new = resolve(self.new, new_id);
+        match (&old.type_def, &new.type_def) {
+            (TypeDef::Variant(old_variants), TypeDef::Variant(new_variants)) => {
+                let ty = old.path.ident().unwrap_or_default();
+                self.diff_variants(&ty, &old_variants.variants, &new_variants.variants);
+            }
+            (TypeDef::Composite(old), TypeDef::Composite(new)) => {
+                for (old, new) in old.fields.iter().zip(&new.fields) {
+                    self.diff(old.ty.id, new.ty.id);
+                }
+            }
+            (TypeDef::Sequence(old), TypeDef::Sequence(new)) => {
+                self.diff(old.type_param.id, new.type_param.id)
+            }
+            (TypeDef::Array(old), TypeDef::Array(new)) => {
+                self.diff(old.type_param.id, new.type_param.id)
+            }
+            (TypeDef::Compact(old), TypeDef::Compact(new)) => {
+                self.diff(old.type_param.id, new.type_param.id)
+            }
+            (TypeDef::Tuple(old), TypeDef::Tuple(new)) => {
+                for (old, new) in old.fields.iter().zip(&new.fields) {
+                    self.diff(old.id, new.id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn diff_variants(
+        &mut self,
+        ty: &str,
+        old: &[Variant<PortableForm>],
+        new: &[Variant<PortableForm>],
+    ) {
+        let mut matched = BTreeSet::new();
+        for old_variant in old {
+            let by_name = new.iter().find(|new| new.name == old_variant.name);
+            let by_index = new.iter().find(|new| new.index == old_variant.index);
+            let matching = match (by_name, by_index) {
+                (Some(new_variant), _) => {
+                    if new_variant.index != old_variant.index {
+                        self.diff.changes.push(VariantChange::Reindexed {
+                            ty: ty.into(),
+                            name: old_variant.name.clone(),
+                            old: old_variant.index,
+                            new: new_variant.index,
+                        });
+                    }
+                    new_variant
+                }
+                (None, Some(new_variant)) => {
+                    self.diff.changes.push(VariantChange::Renamed {
+                        ty: ty.into(),
+                        index: old_variant.index,
+                        old: old_variant.name.clone(),
+                        new: new_variant.name.clone(),
+                    });
+                    new_variant
+                }
+                (None, None) => {
+                    self.diff.changes.push(VariantChange::Removed {
+                        ty: ty.into(),
+                        name: old_variant.name.clone(),
+                        index: old_variant.index,
+                    });
+                    continue;
+                }
+            };
+            matched.insert(matching.index);
+            for (old, new) in old_variant.fields.iter().zip(&matching.fields) {
+                self.diff(old.ty.id, new.ty.id);
+            }
+        }
+        for new_variant in new {
+            if !matched.contains(&new_variant.index) {
+                self.diff.changes.push(VariantChange::Added {
+                    ty: ty.into(),
+                    name: new_variant.name.clone(),
+                    index: new_variant.index,
+                });
+            }
+        }
+    }
+}
+
+fn resolve(registry: &PortableRegistry, id: u32) -> &Type<PortableForm> {
+    registry.resolve(id).expect("type is registered")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PopApiError;
+    use scale_info::{MetaType, Registry, TypeInfo};
+
+    fn registry_of<T: TypeInfo + 'static>() -> PortableRegistry {
+        let mut registry = Registry::new();
+        registry.register_type(&MetaType::new::<T>());
+        registry.into()
+    }
+
+    #[allow(dead_code)]
+    mod v1 {
+        #[derive(scale_info::TypeInfo)]
+        pub enum Error {
+            Kept,
+            Old,
+            Nested(Inner),
+            Moved,
+            #[codec(index = 6)]
+            Dropped,
+        }
+
+        #[derive(scale_info::TypeInfo)]
+        pub enum Inner {
+            Leaf,
+        }
+    }
+
+    #[allow(dead_code)]
+    mod v2 {
+        #[derive(scale_info::TypeInfo)]
+        pub enum Error {
+            Kept,
+            Renamed,
+            Nested(Inner),
+            Fresh,
+            #[codec(index = 5)]
+            Moved,
+        }
+
+        #[derive(scale_info::TypeInfo)]
+        pub enum Inner {
+            Leaf,
+            Branch,
+        }
+    }
+
+    #[test]
+    fn test_identical_registries() {
+        let registry = registry_of::<PopApiError>();
+        let diff = diff_registries(&registry, &registry, &["PopApiError"]);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "no wire format changes");
+    }
+
+    #[test]
+    fn test_categorized_changes() {
+        let diff = diff_registries(
+            &registry_of::<v1::Error>(),
+            &registry_of::<v2::Error>(),
+            &["Error"],
+        );
+        assert_eq!(
+            diff.changes,
+            [
+                VariantChange::Renamed {
+                    ty: "Error".into(),
+                    index: 1,
+                    old: "Old".into(),
+                    new: "Renamed".into(),
+                },
+                VariantChange::Added {
+                    ty: "Inner".into(),
+                    name: "Branch".into(),
+                    index: 1,
+                },
+                VariantChange::Reindexed {
+                    ty: "Error".into(),
+                    name: "Moved".into(),
+                    old: 3,
+                    new: 5,
+                },
+                VariantChange::Removed {
+                    ty: "Error".into(),
+                    name: "Dropped".into(),
+                    index: 6,
+                },
+                VariantChange::Added {
+                    ty: "Error".into(),
+                    name: "Fresh".into(),
+                    index: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "renamed Error::Old to Error::Renamed at index 1\n\
+             added Inner::Branch at index 1\n\
+             reindexed Error::Moved from 3 to 5\n\
+             removed Error::Dropped at index 6\n\
+             added Error::Fresh at index 3\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_root() {
+        let registry = registry_of::<PopApiError>();
+        let diff = diff_registries(&registry, &registry_of::<v1::Error>(), &["PopApiError"]);
+        assert_eq!(diff.missing_roots, ["PopApiError"]);
+        assert_eq!(diff.to_string(), "missing root PopApiError\n");
+    }
+}
@@ -0,0 +1,301 @@
+// The wire layout of the polkadot sdk `DispatchError` and the part of its
+// mapping into a `PopApiError` that doesn't depend on `sp-runtime`, e.g. for
+// off-chain tools decoding `ExtrinsicFailed` events.
+use crate::{
+    ArithmeticError, DecodeError, FungiblesError, ModuleError, PopApiError, TokenError,
+    TransactionalError, UseCaseError,
+};
+use parity_scale_codec::{Decode, Encode};
+
+// Index of `pallet-assets` within the runtime.
+pub const ASSETS_PALLET_INDEX: u8 = 52;
+// Index of `pallet-balances` within the runtime.
+pub const BALANCES_PALLET_INDEX: u8 = 10;
+// Index of `frame_system` within the runtime.
+pub const SYSTEM_PALLET_INDEX: u8 = 0;
+
+// Refines a module error into a top-level error for known `frame_system`
+// errors, into a use case error when the pallet and its error are known, and
+// otherwise returns the module error as is.
+pub fn refine_module_error(error: ModuleError) -> PopApiError {
+    if let Some(error) = system_error(error) {
+        return error;
+    }
+    match fungibles_from_pallet_error(error.index, error.error) {
+        Some(error) => PopApiError::UseCase(UseCaseError::Fungibles(error)),
+        None => PopApiError::Module(error),
+    }
+}
+
+// Refines an error of the approval path, i.e. `approve` and
+// `increase_allowance`. The pallets report an allowance that would overflow as
+// a plain arithmetic overflow, which can't be told apart from any other
+// overflow once converted. Errors other than an overflow are returned as is.
+pub fn refine_approval_error(error: PopApiError) -> PopApiError {
+    match error {
+        PopApiError::Arithmetic(ArithmeticError::Overflow) => PopApiError::UseCase(
+            UseCaseError::Fungibles(FungiblesError::CannotIncreaseAllowance),
+        ),
+        error => error,
+    }
+}
+
+// Maps `frame_system` errors that have a top-level counterpart. The error
+// indices of `frame_system` are:
+// 0. `InvalidSpecName`
+// 1. `SpecVersionNeedsToIncrease`
+// 2. `FailedToExtractRuntimeVersion`
+// 3. `NonDefaultComposite`
+// 4. `NonZeroRefCount`, the account can't be purged due to its references.
+// 5. `CallFiltered`, the call isn't allowed for the origin.
+// 6. `NothingAuthorized`
+// 7. `Unauthorized`
+fn system_error(error: ModuleError) -> Option<PopApiError> {
+    match (error.index, error.error) {
+        (SYSTEM_PALLET_INDEX, 4) => Some(PopApiError::ConsumerRemaining),
+        (SYSTEM_PALLET_INDEX, 5) => Some(PopApiError::BadOrigin),
+        _ => None,
+    }
+}
+
+// Maps an error of `pallet-assets` or `pallet-balances` to its fungibles use
+// case error, `None` if the error has no fungibles counterpart.
+pub fn fungibles_from_pallet_error(pallet_index: u8, error_index: u8) -> Option<FungiblesError> {
+    use FungiblesError::*;
+    match (pallet_index, error_index) {
+        // `BalanceLow`
+        (ASSETS_PALLET_INDEX, 0) => Some(InsufficientBalance),
+        // `NoAccount`
+        (ASSETS_PALLET_INDEX, 1) => Some(NoAccount),
+        // `NoPermission`
+        (ASSETS_PALLET_INDEX, 2) => Some(NoPermission),
+        // `Unknown`
+        (ASSETS_PALLET_INDEX, 3) => Some(Unknown),
+        // `InUse`
+        (ASSETS_PALLET_INDEX, 5) => Some(InUse),
+        // `MinBalanceZero`
+        (ASSETS_PALLET_INDEX, 7) => Some(MinBalanceZero),
+        // `Unapproved`
+        (ASSETS_PALLET_INDEX, 10) => Some(InsufficientAllowance),
+        // `AssetNotLive`, which doesn't tell whether the asset is frozen or
+        // being destroyed.
+        (ASSETS_PALLET_INDEX, 16) => Some(AssetNotLive),
+        // `InsufficientBalance`
+        (BALANCES_PALLET_INDEX, 2) => Some(InsufficientBalance),
+        // `ExistentialDeposit`
+        (BALANCES_PALLET_INDEX, 3) => Some(BelowMinimum),
+        // `DeadAccount`
+        (BALANCES_PALLET_INDEX, 6) => Some(NoAccount),
+        _ => None,
+    }
+}
+
+// Mirror of `DispatchError` with the same encoding. The messages of `Other`
+// and `Module` are not encoded by `DispatchError`, so they can't be decoded
+// either: `Other` converts to `PopApiError::Other(0)`, just like a decoded
+// `DispatchError` does. `Module` carries all four bytes of the pallet error,
+// of which the conversion only uses the first.
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode)]
+pub enum RawDispatchError {
+    Other,
+    CannotLookup,
+    BadOrigin,
+    Module { index: u8, error: [u8; 4] },
+    ConsumerRemaining,
+    NoProviders,
+    TooManyConsumers,
+    Token(TokenError),
+    Arithmetic(ArithmeticError),
+    Transactional(TransactionalError),
+    Exhausted,
+    Corruption,
+    Unavailable,
+    RootNotAllowed,
+}
+
+// Decodes the error at the start of the bytes, e.g. the fields of an
+// `ExtrinsicFailed` event. Tolerant of the data following the error, such as
+// the `DispatchInfo` of the event, which is ignored.
+pub fn decode_dispatch_error(bytes: &[u8]) -> Result<RawDispatchError, DecodeError> {
+    let mut input = bytes;
+    // The decoder rejects the last byte it read, e.g. an unknown variant index.
+    RawDispatchError::decode(&mut input).map_err(|error| DecodeError {
+        position: (bytes.len() - input.len()).saturating_sub(1),
+        error,
+    })
+}
+
+// The same mapping as `From<DispatchError> for PopApiError`.
+impl From<RawDispatchError> for PopApiError {
+    fn from(error: RawDispatchError) -> Self {
+        use PopApiError::*;
+        match error {
+            RawDispatchError::Other => Other(0),
+            RawDispatchError::CannotLookup => CannotLookup,
+            RawDispatchError::BadOrigin => BadOrigin,
+            RawDispatchError::Module { index, error } => refine_module_error(ModuleError {
+                index,
+                error: error[0],
+            }),
+            RawDispatchError::ConsumerRemaining => ConsumerRemaining,
+            RawDispatchError::NoProviders => NoProviders,
+            RawDispatchError::TooManyConsumers => TooManyConsumers,
+            RawDispatchError::Token(error) => Token(error),
+            RawDispatchError::Arithmetic(error) => Arithmetic(error),
+            RawDispatchError::Transactional(error) => Transactional(error),
+            RawDispatchError::Exhausted => Exhausted,
+            RawDispatchError::Corruption => Corruption,
+            RawDispatchError::Unavailable => Unavailable,
+            RawDispatchError::RootNotAllowed => RootNotAllowed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every variant, `Module` with a few pallet errors.
+    fn all_variants() -> Vec<RawDispatchError> {
+        use RawDispatchError::*;
+        let mut errors = vec![
+            Other,
+            CannotLookup,
+            BadOrigin,
+            Module {
+                index: ASSETS_PALLET_INDEX,
+                error: [0, 0, 0, 0],
+            },
+            Module {
+                index: SYSTEM_PALLET_INDEX,
+                error: [5, 0, 0, 0],
+            },
+            Module {
+                index: 200,
+                error: [1, 2, 3, 4],
+            },
+            ConsumerRemaining,
+            NoProviders,
+            TooManyConsumers,
+        ];
+        errors.extend(TokenError::all().iter().map(|error| Token(*error)));
+        errors.extend(
+            ArithmeticError::all()
+                .iter()
+                .map(|error| Arithmetic(*error)),
+        );
+        errors.extend(
+            TransactionalError::all()
+                .iter()
+                .map(|error| Transactional(*error)),
+        );
+        errors.extend([Exhausted, Corruption, Unavailable, RootNotAllowed]);
+        errors
+    }
+
+    #[test]
+    fn test_decode_dispatch_error() {
+        for error in all_variants() {
+            assert_eq!(decode_dispatch_error(&error.encode()), Ok(error));
+        }
+        // The `DispatchInfo` following the error in an `ExtrinsicFailed` event.
+        assert_eq!(
+            decode_dispatch_error(&[3, 52, 0, 0, 0, 0, 9, 9, 9]),
+            Ok(RawDispatchError::Module {
+                index: ASSETS_PALLET_INDEX,
+                error: [0, 0, 0, 0]
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_dispatch_error_rejects() {
+        let error = |bytes: &[u8]| decode_dispatch_error(bytes).unwrap_err().position;
+        // Unknown variant.
+        assert_eq!(error(&[14]), 0);
+        // Unknown token error.
+        assert_eq!(error(&[7, 10]), 1);
+        // Truncated module error.
+        assert_eq!(error(&[3, 52, 0]), 1);
+        assert!(decode_dispatch_error(&[]).is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        use PopApiError::*;
+        let convert = |bytes: &[u8]| PopApiError::from(decode_dispatch_error(bytes).unwrap());
+        assert_eq!(convert(&[0]), Other(0));
+        assert_eq!(
+            convert(&[3, ASSETS_PALLET_INDEX, 0, 0, 0, 0]),
+            UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance))
+        );
+        assert_eq!(convert(&[3, SYSTEM_PALLET_INDEX, 5, 0, 0, 0]), BadOrigin);
+        assert_eq!(
+            convert(&[3, 200, 1, 2, 3, 4]),
+            Module(ModuleError {
+                index: 200,
+                error: 1
+            })
+        );
+        assert_eq!(convert(&[7, 5]), Token(TokenError::Frozen));
+    }
+
+    #[test]
+    fn test_refine_approval_error() {
+        use PopApiError::*;
+        assert_eq!(
+            refine_approval_error(Arithmetic(ArithmeticError::Overflow)),
+            UseCase(UseCaseError::Fungibles(
+                FungiblesError::CannotIncreaseAllowance
+            ))
+        );
+        for error in [
+            Arithmetic(ArithmeticError::Underflow),
+            UseCase(UseCaseError::Fungibles(
+                FungiblesError::InsufficientAllowance,
+            )),
+            BadOrigin,
+        ] {
+            assert_eq!(refine_approval_error(error), error);
+        }
+    }
+
+    // The mirror agrees with the real `DispatchError` on the bytes and on the
+    // conversion, in both directions.
+    #[cfg(feature = "runtime")]
+    #[test]
+    fn test_matches_dispatch_error() {
+        use crate::fuzzing::arbitrary_dispatch_error;
+        use arbitrary::Unstructured;
+        use sp_runtime::DispatchError;
+
+        for error in all_variants() {
+            let bytes = error.encode();
+            let expected = DispatchError::decode(&mut &bytes[..]).unwrap();
+            assert_eq!(expected.encode(), bytes, "{error:?}");
+            assert_eq!(PopApiError::from(error), PopApiError::from(expected));
+        }
+        for seed in 0..=u16::MAX {
+            let seed = seed.to_le_bytes();
+            let Ok(error) = arbitrary_dispatch_error(&mut Unstructured::new(&seed)) else {
+                continue;
+            };
+            let bytes = error.encode();
+            let raw = decode_dispatch_error(&bytes).unwrap();
+            assert_eq!(raw.encode(), bytes, "{error:?}");
+            // The message of `Other` is not encoded.
+            let decoded = DispatchError::decode(&mut &bytes[..]).unwrap();
+            assert_eq!(PopApiError::from(raw), PopApiError::from(decoded));
+        }
+        // Every byte sequence is either rejected by both or decodes the same.
+        for first in 0..=u8::MAX {
+            for second in 0..=u8::MAX {
+                let bytes = [first, second, 0, 0, 0, 0];
+                let raw = decode_dispatch_error(&bytes).map(|error| error.encode());
+                let expected = DispatchError::decode(&mut &bytes[..]).map(|error| error.encode());
+                assert_eq!(raw.is_ok(), expected.is_ok(), "{bytes:?}");
+                assert_eq!(raw.ok(), expected.ok(), "{bytes:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,401 @@
+// Decoding of values whose type is only known at runtime, e.g. by tooling
+// receiving a type id and raw bytes of a use case it wasn't built with. Covers
+// the constructs of this crate's types: enums, structs, tuples, sequences,
+// arrays and primitives.
+use core::fmt;
+use parity_scale_codec::{Compact, Decode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+// A decoded value.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Value {
+    /// A variant of an enum and its fields.
+    Variant {
+        name: String,
+        index: u8,
+        fields: Fields,
+    },
+    /// The fields of a struct.
+    Composite(Fields),
+    /// The elements of an array, a sequence or a tuple.
+    Sequence(Vec<Value>),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    /// Any unsigned integer up to 128 bits.
+    Unsigned(u128),
+    /// Any signed integer up to 128 bits.
+    Signed(i128),
+}
+
+// The fields of a struct or variant, named or unnamed as declared.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Fields {
+    Named(Vec<(String, Value)>),
+    Unnamed(Vec<Value>),
+}
+
+// Why bytes couldn't be decoded, with the position of the offending byte where
+// there is one.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DynError {
+    /// The type id is not part of the registry.
+    UnknownType(u32),
+    /// The bytes ended within a value.
+    UnexpectedEnd { position: usize },
+    /// The enum has no variant with the index.
+    UnknownVariant { position: usize, index: u8 },
+    /// A primitive with an invalid encoding, e.g. a `bool` of 2.
+    InvalidValue { position: usize },
+    /// The value was decoded, but bytes are left.
+    TrailingBytes { position: usize },
+    /// The type uses a construct the decoder doesn't support, e.g. a compact.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynError::UnknownType(id) => write!(f, "unknown type id {id}"),
+            DynError::UnexpectedEnd { position } => {
+                write!(f, "unexpected end of input at byte {position}")
+            }
+            DynError::UnknownVariant { position, index } => {
+                write!(f, "unknown variant index {index} at byte {position}")
+            }
+            DynError::InvalidValue { position } => write!(f, "invalid value at byte {position}"),
+            DynError::TrailingBytes { position } => {
+                write!(f, "trailing bytes from byte {position}")
+            }
+            DynError::Unsupported(construct) => write!(f, "{construct} is not supported"),
+        }
+    }
+}
+
+// Decodes exactly one value of the type from the bytes.
+pub fn decode_dynamic(
+    registry: &PortableRegistry,
+    type_id: u32,
+    bytes: &[u8],
+) -> Result<Value, DynError> {
+    let mut decoder = Decoder {
+        registry,
+        bytes,
+        position: 0,
+    };
+    let value = decoder.decode(type_id)?;
+    if decoder.position < bytes.len() {
+        return Err(DynError::TrailingBytes {
+            position: decoder.position,
+        });
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    registry: &'a PortableRegistry,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn decode(&mut self, id: u32) -> Result<Value, DynError> {
+        let ty = self.registry.resolve(id).ok_or(DynError::UnknownType(id))?;
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                Ok(Value::Composite(self.decode_fields(&composite.fields)?))
+            }
+            TypeDef::Variant(variants) => {
+                let position = self.position;
+                let index = self.take(1)?[0];
+                let variant = variants
+                    .variants
+                    .iter()
+                    .find(|variant| variant.index == index)
+                    .ok_or(DynError::UnknownVariant { position, index })?;
+                Ok(Value::Variant {
+                    name: variant.name.clone(),
+                    index,
+                    fields: self.decode_fields(&variant.fields)?,
+                })
+            }
+            TypeDef::Sequence(sequence) => {
+                let len = self.compact_len()?;
+                (0..len)
+                    .map(|_| self.decode(sequence.type_param.id))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Sequence)
+            }
+            TypeDef::Array(array) => (0..array.len)
+                .map(|_| self.decode(array.type_param.id))
+                .collect::<Result<_, _>>()
+                .map(Value::Sequence),
+            TypeDef::Tuple(tuple) => tuple
+                .fields
+                .iter()
+                .map(|field| self.decode(field.id))
+                .collect::<Result<_, _>>()
+                .map(Value::Sequence),
+            TypeDef::Primitive(primitive) => self.decode_primitive(primitive),
+            TypeDef::Compact(_) => Err(DynError::Unsupported("compact")),
+            TypeDef::BitSequence(_) => Err(DynError::Unsupported("bit sequence")),
+        }
+    }
+
+    fn decode_fields(&mut self, fields: &[Field<PortableForm>]) -> Result<Fields, DynError> {
+        if fields.iter().all(|field| field.name.is_some()) && !fields.is_empty() {
+            fields
+                .iter()
+                .map(|field| {
+                    Ok((
+                        field.name.clone().unwrap_or_default(),
+                        self.decode(field.ty.id)?,
+                    ))
+                })
+                .collect::<Result<_, _>>()
+                .map(Fields::Named)
+        } else {
+            fields
+                .iter()
+                .map(|field| self.decode(field.ty.id))
+                .collect::<Result<_, _>>()
+                .map(Fields::Unnamed)
+        }
+    }
+
+    fn decode_primitive(&mut self, primitive: &TypeDefPrimitive) -> Result<Value, DynError> {
+        use TypeDefPrimitive::*;
+        let position = self.position;
+        Ok(match primitive {
+            Bool => match self.take(1)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return Err(DynError::InvalidValue { position }),
+            },
+            Char => char::from_u32(self.unsigned(4)? as u32)
+                .map(Value::Char)
+                .ok_or(DynError::InvalidValue { position })?,
+            Str => {
+                let len = self.compact_len()?;
+                String::from_utf8(self.take(len)?.to_vec())
+                    .map(Value::Str)
+                    .map_err(|_| DynError::InvalidValue { position })?
+            }
+            U8 => Value::Unsigned(self.unsigned(1)?),
+            U16 => Value::Unsigned(self.unsigned(2)?),
+            U32 => Value::Unsigned(self.unsigned(4)?),
+            U64 => Value::Unsigned(self.unsigned(8)?),
+            U128 => Value::Unsigned(self.unsigned(16)?),
+            I8 => Value::Signed(self.signed(1)?),
+            I16 => Value::Signed(self.signed(2)?),
+            I32 => Value::Signed(self.signed(4)?),
+            I64 => Value::Signed(self.signed(8)?),
+            I128 => Value::Signed(self.signed(16)?),
+            U256 | I256 => return Err(DynError::Unsupported("256 bit integer")),
+        })
+    }
+
+    // A little endian integer of `len` bytes.
+    fn unsigned(&mut self, len: usize) -> Result<u128, DynError> {
+        let mut bytes = [0; 16];
+        bytes[..len].copy_from_slice(self.take(len)?);
+        Ok(u128::from_le_bytes(bytes))
+    }
+
+    // A little endian two's complement integer of `len` bytes.
+    fn signed(&mut self, len: usize) -> Result<i128, DynError> {
+        let bytes = self.take(len)?;
+        let fill = if bytes[len - 1] & 0x80 == 0 {
+            0
+        } else {
+            u8::MAX
+        };
+        let mut extended = [fill; 16];
+        extended[..len].copy_from_slice(bytes);
+        Ok(i128::from_le_bytes(extended))
+    }
+
+    // The compact encoded length of a sequence or string. Its first byte tells
+    // how many bytes it occupies.
+    fn compact_len(&mut self) -> Result<usize, DynError> {
+        let position = self.position;
+        let first = *self.bytes.get(position).ok_or(DynError::UnexpectedEnd {
+            position: self.bytes.len(),
+        })?;
+        let len = match first & 0b11 {
+            0 => 1,
+            1 => 2,
+            2 => 4,
+            _ => usize::from(first >> 2) + 5,
+        };
+        let mut input = self.take(len)?;
+        Compact::<u32>::decode(&mut input)
+            .map(|len| len.0 as usize)
+            .map_err(|_| DynError::InvalidValue { position })
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], DynError> {
+        let bytes =
+            self.bytes
+                .get(self.position..self.position + len)
+                .ok_or(DynError::UnexpectedEnd {
+                    position: self.bytes.len(),
+                })?;
+        self.position += len;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explain::registry_of;
+    use crate::{FungiblesError, ModuleError, PopApiError, UseCaseError};
+    use parity_scale_codec::Encode;
+
+    fn variant(name: &str, index: u8, fields: Fields) -> Value {
+        Value::Variant {
+            name: name.into(),
+            index,
+            fields,
+        }
+    }
+
+    fn module_error(index: u8, error: u8) -> Value {
+        Value::Composite(Fields::Named(vec![
+            ("index".into(), Value::Unsigned(index.into())),
+            ("error".into(), Value::Unsigned(error.into())),
+        ]))
+    }
+
+    fn decode<T: scale_info::TypeInfo + 'static>(bytes: &[u8]) -> Result<Value, DynError> {
+        let (registry, id) = registry_of::<T>();
+        decode_dynamic(&registry, id, bytes)
+    }
+
+    #[test]
+    fn test_module_error() {
+        let error = ModuleError {
+            index: 52,
+            error: 3,
+        };
+        assert_eq!(
+            decode::<ModuleError>(&error.encode()),
+            Ok(module_error(52, 3))
+        );
+        let error = PopApiError::Module(error);
+        assert_eq!(
+            decode::<PopApiError>(&error.encode()),
+            Ok(variant(
+                "Module",
+                3,
+                Fields::Unnamed(vec![module_error(52, 3)])
+            ))
+        );
+    }
+
+    #[test]
+    fn test_nested_use_case() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        let no_account = variant("NoAccount", 6, Fields::Unnamed(Vec::new()));
+        let fungibles = variant("Fungibles", 0, Fields::Unnamed(vec![no_account]));
+        assert_eq!(
+            decode::<PopApiError>(&error.encode()),
+            Ok(variant("UseCase", 14, Fields::Unnamed(vec![fungibles])))
+        );
+        let error = PopApiError::UseCase(UseCaseError::Module(ModuleError { index: 1, error: 2 }));
+        let module = variant("Module", 1, Fields::Unnamed(vec![module_error(1, 2)]));
+        assert_eq!(
+            decode::<PopApiError>(&error.encode()),
+            Ok(variant("UseCase", 14, Fields::Unnamed(vec![module])))
+        );
+    }
+
+    #[test]
+    fn test_every_variant() {
+        for error in PopApiError::all_variants() {
+            let Ok(Value::Variant { name, index, .. }) = decode::<PopApiError>(&error.encode())
+            else {
+                panic!("{error:?} didn't decode to a variant");
+            };
+            assert_eq!(index, u32::from(error).to_le_bytes()[0]);
+            assert!(format!("{error:?}").starts_with(&name), "{error:?}");
+        }
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(
+            decode::<(bool, i16, String, [u32; 2])>(&(true, -2i16, "pop", [1u32, 2]).encode()),
+            Ok(Value::Sequence(vec![
+                Value::Bool(true),
+                Value::Signed(-2),
+                Value::Str("pop".into()),
+                Value::Sequence(vec![Value::Unsigned(1), Value::Unsigned(2)]),
+            ]))
+        );
+        assert_eq!(
+            decode::<Vec<u8>>(&vec![7u8; 3].encode()),
+            Ok(Value::Sequence(vec![Value::Unsigned(7); 3]))
+        );
+        assert_eq!(
+            decode::<bool>(&[2]),
+            Err(DynError::InvalidValue { position: 0 })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let (registry, _) = registry_of::<PopApiError>();
+        assert_eq!(
+            decode_dynamic(&registry, 1000, &[0]),
+            Err(DynError::UnknownType(1000))
+        );
+        // Short input, the pallet's error index is missing.
+        assert_eq!(
+            decode::<PopApiError>(&[3, 52]),
+            Err(DynError::UnexpectedEnd { position: 2 })
+        );
+        assert_eq!(
+            decode::<PopApiError>(&[]),
+            Err(DynError::UnexpectedEnd { position: 0 })
+        );
+        assert_eq!(
+            decode::<PopApiError>(&[14, 0, 10]),
+            Err(DynError::UnknownVariant {
+                position: 2,
+                index: 10
+            })
+        );
+        assert_eq!(
+            decode::<PopApiError>(&[2, 0]),
+            Err(DynError::TrailingBytes { position: 1 })
+        );
+        assert_eq!(
+            decode::<String>(&[8, b'p']),
+            Err(DynError::UnexpectedEnd { position: 2 })
+        );
+        assert_eq!(
+            DynError::UnknownVariant {
+                position: 2,
+                index: 9
+            }
+            .to_string(),
+            "unknown variant index 9 at byte 2"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let value = decode::<PopApiError>(&[3, 52, 3]).unwrap();
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"Variant":{"name":"Module","index":3,"fields":{"Unnamed":[{"Composite":{"Named":[["index",{"Unsigned":52}],["error",{"Unsigned":3}]]}}]}}}"#
+        );
+    }
+}
@@ -0,0 +1,637 @@
+// Explains the SCALE encoding of a value byte by byte, using its type
+// information to tell which variant or field every byte belongs to, e.g.
+// `[14] UseCase | [0] Fungibles | [3] InsufficientBalance`. The layout of
+// types, i.e. the bounds of their encoded length, is computed from the type
+// information as well.
+use core::fmt;
+use parity_scale_codec::{Compact, Decode, Encode};
+use scale_info::{
+    form::PortableForm, Field, MetaType, PortableRegistry, Registry, Type, TypeDef,
+    TypeDefPrimitive, TypeInfo,
+};
+
+// The bytes of a value together with what they encode.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Segment {
+    pub bytes: Vec<u8>,
+    // The variant, or the field and its value, e.g. `index: 1`.
+    pub label: String,
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.bytes, self.label)
+    }
+}
+
+// The encoding of a value split into segments, in encoding order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EncodingReport {
+    pub segments: Vec<Segment>,
+}
+
+impl fmt::Display for EncodingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" | ")?;
+            }
+            write!(f, "{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn explain_encoding<T: Encode + TypeInfo + 'static>(value: &T) -> EncodingReport {
+    let mut registry = Registry::new();
+    let id = registry.register_type(&MetaType::new::<T>()).id;
+    let registry = PortableRegistry::from(registry);
+    let encoded = value.encode();
+    let mut explainer = Explainer {
+        registry: &registry,
+        encoded: &encoded,
+        position: 0,
+        segments: Vec::new(),
+    };
+    explainer.explain(id, None);
+    EncodingReport {
+        segments: explainer.segments,
+    }
+}
+
+struct Explainer<'a> {
+    registry: &'a PortableRegistry,
+    encoded: &'a [u8],
+    position: usize,
+    segments: Vec<Segment>,
+}
+
+impl Explainer<'_> {
+    // The encoding was created from the type, so it always matches its type
+    // information.
+    fn explain(&mut self, id: u32, name: Option<&str>) {
+        let ty = self.registry.resolve(id).expect("type is registered");
+        match &ty.type_def {
+            TypeDef::Composite(composite) => self.explain_fields(&composite.fields),
+            TypeDef::Variant(variants) => {
+                let index = self.encoded[self.position];
+                let variant = variants
+                    .variants
+                    .iter()
+                    .find(|variant| variant.index == index)
+                    .expect("encoded variant exists");
+                self.push(1, variant.name.clone());
+                self.explain_fields(&variant.fields);
+            }
+            TypeDef::Sequence(sequence) => {
+                let len = self.compact("length");
+                for _ in 0..len {
+                    self.explain(sequence.type_param.id, None);
+                }
+            }
+            TypeDef::Array(array) => {
+                for _ in 0..array.len {
+                    self.explain(array.type_param.id, None);
+                }
+            }
+            TypeDef::Tuple(tuple) => {
+                for field in &tuple.fields {
+                    self.explain(field.id, None);
+                }
+            }
+            TypeDef::Compact(_) => {
+                self.compact(name.unwrap_or("compact"));
+            }
+            TypeDef::Primitive(primitive) => self.primitive(primitive, name),
+            TypeDef::BitSequence(_) => {
+                self.push(self.encoded.len() - self.position, "bits".into());
+            }
+        }
+    }
+
+    fn explain_fields(&mut self, fields: &[Field<PortableForm>]) {
+        for field in fields {
+            let name = field.name.as_deref().or(field.type_name.as_deref());
+            self.explain(field.ty.id, name);
+        }
+    }
+
+    fn primitive(&mut self, primitive: &TypeDefPrimitive, name: Option<&str>) {
+        use TypeDefPrimitive::*;
+        match primitive {
+            Bool => self.decode::<bool>(name, "bool"),
+            Char => self.decode::<u32>(name, "char"),
+            Str => self.decode::<String>(name, "str"),
+            U8 => self.decode::<u8>(name, "u8"),
+            U16 => self.decode::<u16>(name, "u16"),
+            U32 => self.decode::<u32>(name, "u32"),
+            U64 => self.decode::<u64>(name, "u64"),
+            U128 => self.decode::<u128>(name, "u128"),
+            I8 => self.decode::<i8>(name, "i8"),
+            I16 => self.decode::<i16>(name, "i16"),
+            I32 => self.decode::<i32>(name, "i32"),
+            I64 => self.decode::<i64>(name, "i64"),
+            I128 => self.decode::<i128>(name, "i128"),
+            U256 | I256 => self.push(32, name.unwrap_or("256 bit integer").into()),
+        }
+    }
+
+    fn decode<D: Decode + fmt::Debug>(&mut self, name: Option<&str>, type_name: &str) {
+        let mut input = &self.encoded[self.position..];
+        let value = D::decode(&mut input).expect("encoded value matches its type");
+        let len = self.encoded.len() - self.position - input.len();
+        self.push(len, format!("{}: {value:?}", name.unwrap_or(type_name)));
+    }
+
+    // Consumes a compact encoded integer, e.g. the length prefix of a `Vec`.
+    fn compact(&mut self, name: &str) -> u128 {
+        let mut input = &self.encoded[self.position..];
+        let value = Compact::<u128>::decode(&mut input)
+            .expect("encoded compact is valid")
+            .0;
+        let len = self.encoded.len() - self.position - input.len();
+        self.push(len, format!("{name}: {value}"));
+        value
+    }
+
+    fn push(&mut self, len: usize, label: String) {
+        let bytes = self.encoded[self.position..self.position + len].to_vec();
+        self.position += len;
+        self.segments.push(Segment { bytes, label });
+    }
+}
+
+// Depth up to which types are walked when computing their layout, anything
+// nested deeper is considered unbounded.
+const MAX_DEPTH: usize = 16;
+
+// The encoded length of a variant.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariantLayout {
+    pub name: String,
+    pub index: u8,
+    pub min_len: usize,
+    // `None` if the length is unbounded, e.g. for a variant holding a `Vec`.
+    pub max_len: Option<usize>,
+}
+
+pub(crate) fn registry_of<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+    let mut registry = Registry::new();
+    let id = registry.register_type(&MetaType::new::<T>()).id;
+    (registry.into(), id)
+}
+
+// The layout of every variant of the enum `T`, empty if `T` isn't an enum.
+// Lengths include the byte of the variant index.
+pub fn variant_layout<T: TypeInfo + 'static>() -> Vec<VariantLayout> {
+    let (registry, id) = registry_of::<T>();
+    let TypeDef::Variant(variants) = &resolve(&registry, id).type_def else {
+        return Vec::new();
+    };
+    variants
+        .variants
+        .iter()
+        .map(|variant| {
+            let (min_len, max_len) = fields_len(&registry, &variant.fields, 1);
+            VariantLayout {
+                name: variant.name.clone(),
+                index: variant.index,
+                min_len: 1 + min_len,
+                max_len: max_len.map(|len| 1 + len),
+            }
+        })
+        .collect()
+}
+
+// Whether every value of `T` encodes to at most `budget` bytes, e.g. the four
+// bytes of a status code.
+pub fn fits_status_budget<T: TypeInfo + 'static>(budget: usize) -> bool {
+    check_budget::<T>(budget).is_ok()
+}
+
+// The longest encoding of a type exceeding a budget, and the variants and
+// fields leading to it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BudgetReport {
+    pub budget: usize,
+    // `None` if the length is unbounded.
+    pub max_len: Option<usize>,
+    // E.g. `["PopApiError::UseCase", "UseCaseError::Fungibles"]`.
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for BudgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` encodes to ", self.path.join(" -> "))?;
+        match self.max_len {
+            Some(max_len) => write!(f, "up to {max_len} bytes")?,
+            None => f.write_str("an unbounded number of bytes")?,
+        }
+        write!(f, ", exceeding the budget of {} bytes", self.budget)
+    }
+}
+
+// Checks that every value of `T` encodes to at most `max_bytes` bytes,
+// otherwise reports the path to the longest encoding. Meant for designing use
+// case enums that have to fit in a status code.
+pub fn check_budget<T: TypeInfo + 'static>(max_bytes: usize) -> Result<(), BudgetReport> {
+    let (registry, id) = registry_of::<T>();
+    let (max_len, path) = longest(&registry, id, 0);
+    if max_len.is_some_and(|max_len| max_len <= max_bytes) {
+        return Ok(());
+    }
+    Err(BudgetReport {
+        budget: max_bytes,
+        max_len,
+        path,
+    })
+}
+
+// The number of enums nested in `T` along its deepest path, each of which
+// encodes its variant index in a byte of its own. E.g. 3 for
+// `PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::Unknown))`.
+pub fn max_nesting_depth<T: TypeInfo + 'static>() -> usize {
+    let (registry, id) = registry_of::<T>();
+    nesting_depth(&registry, id, 0)
+}
+
+fn nesting_depth(registry: &PortableRegistry, id: u32, depth: usize) -> usize {
+    if depth > MAX_DEPTH {
+        return 0;
+    }
+    let fields_depth = |fields: &[Field<PortableForm>]| {
+        fields
+            .iter()
+            .map(|field| nesting_depth(registry, field.ty.id, depth + 1))
+            .max()
+            .unwrap_or(0)
+    };
+    match &resolve(registry, id).type_def {
+        TypeDef::Composite(composite) => fields_depth(&composite.fields),
+        TypeDef::Variant(variants) => {
+            let nested = variants
+                .variants
+                .iter()
+                .map(|variant| fields_depth(&variant.fields))
+                .max()
+                .unwrap_or(0);
+            1 + nested
+        }
+        TypeDef::Sequence(sequence) => nesting_depth(registry, sequence.type_param.id, depth + 1),
+        TypeDef::Array(array) => nesting_depth(registry, array.type_param.id, depth + 1),
+        TypeDef::Tuple(tuple) => tuple
+            .fields
+            .iter()
+            .map(|field| nesting_depth(registry, field.id, depth + 1))
+            .max()
+            .unwrap_or(0),
+        TypeDef::Primitive(_) | TypeDef::Compact(_) | TypeDef::BitSequence(_) => 0,
+    }
+}
+
+// Whether the maximum length `a` is longer than `b`, unbounded being the
+// longest.
+fn longer(a: Option<usize>, b: Option<usize>) -> bool {
+    match (a, b) {
+        (None, Some(_)) => true,
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
+// The maximum encoded length of a type and the path to the longest variant or
+// field. Unlike `encoded_len` it names what makes the encoding long.
+fn longest(registry: &PortableRegistry, id: u32, depth: usize) -> (Option<usize>, Vec<String>) {
+    if depth > MAX_DEPTH {
+        return (None, vec!["...".into()]);
+    }
+    let ty = resolve(registry, id);
+    let name = ty.path.ident().unwrap_or_default();
+    match &ty.type_def {
+        TypeDef::Composite(composite) => longest_fields(registry, &composite.fields, depth + 1),
+        TypeDef::Variant(variants) => {
+            let mut longest_variant: Option<(Option<usize>, Vec<String>)> = None;
+            for variant in &variants.variants {
+                let (max_len, path) = longest_fields(registry, &variant.fields, depth + 1);
+                let max_len = max_len.map(|len| 1 + len);
+                if longest_variant
+                    .as_ref()
+                    .is_none_or(|(longest_len, _)| longer(max_len, *longest_len))
+                {
+                    let mut full_path = vec![format!("{name}::{}", variant.name)];
+                    full_path.extend(path);
+                    longest_variant = Some((max_len, full_path));
+                }
+            }
+            longest_variant.unwrap_or((Some(0), Vec::new()))
+        }
+        TypeDef::Array(array) => {
+            let (max_len, path) = longest(registry, array.type_param.id, depth + 1);
+            (max_len.map(|len| len * array.len as usize), path)
+        }
+        TypeDef::Tuple(tuple) => {
+            let fields = tuple.fields.iter().map(|field| (None, field.id));
+            longest_of(registry, fields, depth + 1)
+        }
+        _ => (encoded_len(registry, id, depth).1, Vec::new()),
+    }
+}
+
+fn longest_fields(
+    registry: &PortableRegistry,
+    fields: &[Field<PortableForm>],
+    depth: usize,
+) -> (Option<usize>, Vec<String>) {
+    let fields = fields
+        .iter()
+        .map(|field| (field.name.as_deref(), field.ty.id));
+    longest_of(registry, fields, depth)
+}
+
+// The total maximum length of a sequence of fields, and the path through the
+// longest of them.
+fn longest_of<'a>(
+    registry: &PortableRegistry,
+    fields: impl Iterator<Item = (Option<&'a str>, u32)>,
+    depth: usize,
+) -> (Option<usize>, Vec<String>) {
+    let mut total = Some(0);
+    let mut longest_field: Option<(Option<usize>, Vec<String>)> = None;
+    for (name, id) in fields {
+        let (max_len, mut path) = longest(registry, id, depth);
+        total = total.zip(max_len).map(|(total, len)| total + len);
+        if longest_field
+            .as_ref()
+            .is_none_or(|(longest_len, _)| longer(max_len, *longest_len))
+        {
+            if let Some(name) = name {
+                path.insert(0, name.into());
+            }
+            longest_field = Some((max_len, path));
+        }
+    }
+    (
+        total,
+        longest_field.map(|(_, path)| path).unwrap_or_default(),
+    )
+}
+
+pub(crate) fn resolve(registry: &PortableRegistry, id: u32) -> &Type<PortableForm> {
+    registry.resolve(id).expect("type is registered")
+}
+
+// The minimum and maximum encoded length of a type, the maximum is `None` if
+// it is unbounded.
+fn encoded_len(registry: &PortableRegistry, id: u32, depth: usize) -> (usize, Option<usize>) {
+    if depth > MAX_DEPTH {
+        return (0, None);
+    }
+    match &resolve(registry, id).type_def {
+        TypeDef::Composite(composite) => fields_len(registry, &composite.fields, depth + 1),
+        TypeDef::Variant(variants) => {
+            let lens = variants
+                .variants
+                .iter()
+                .map(|variant| fields_len(registry, &variant.fields, depth + 1));
+            let min_len = lens.clone().map(|(min, _)| min).min().unwrap_or(0);
+            let max_len = lens
+                .map(|(_, max)| max)
+                .try_fold(0, |acc, max| max.map(|max| acc.max(max)));
+            (1 + min_len, max_len.map(|len| 1 + len))
+        }
+        // The compact length prefix, followed by any number of elements.
+        TypeDef::Sequence(_) | TypeDef::BitSequence(_) => (1, None),
+        TypeDef::Array(array) => {
+            let (min, max) = encoded_len(registry, array.type_param.id, depth + 1);
+            let len = array.len as usize;
+            (min * len, max.map(|max| max * len))
+        }
+        TypeDef::Tuple(tuple) => tuple.fields.iter().fold((0, Some(0)), |acc, field| {
+            add(acc, encoded_len(registry, field.id, depth + 1))
+        }),
+        TypeDef::Primitive(primitive) => {
+            use TypeDefPrimitive::*;
+            let len = match primitive {
+                Bool | U8 | I8 => 1,
+                U16 | I16 => 2,
+                Char | U32 | I32 => 4,
+                U64 | I64 => 8,
+                U128 | I128 => 16,
+                U256 | I256 => 32,
+                Str => return (1, None),
+            };
+            (len, Some(len))
+        }
+        TypeDef::Compact(compact) => {
+            let max_len = match &resolve(registry, compact.type_param.id).type_def {
+                TypeDef::Primitive(TypeDefPrimitive::U8) => Some(2),
+                TypeDef::Primitive(TypeDefPrimitive::U16) => Some(4),
+                TypeDef::Primitive(TypeDefPrimitive::U32) => Some(5),
+                TypeDef::Primitive(TypeDefPrimitive::U64) => Some(9),
+                TypeDef::Primitive(TypeDefPrimitive::U128) => Some(17),
+                _ => None,
+            };
+            (1, max_len)
+        }
+    }
+}
+
+fn fields_len(
+    registry: &PortableRegistry,
+    fields: &[Field<PortableForm>],
+    depth: usize,
+) -> (usize, Option<usize>) {
+    fields.iter().fold((0, Some(0)), |acc, field| {
+        add(acc, encoded_len(registry, field.ty.id, depth))
+    })
+}
+
+fn add(a: (usize, Option<usize>), b: (usize, Option<usize>)) -> (usize, Option<usize>) {
+    (a.0 + b.0, a.1.zip(b.1).map(|(a, b)| a + b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError, ModuleError, PopApiError, UseCaseError};
+
+    #[test]
+    fn test_unit_variant() {
+        let report = explain_encoding(&PopApiError::BadOrigin);
+        assert_eq!(report.to_string(), "[2] BadOrigin");
+    }
+
+    #[test]
+    fn test_nested_enum() {
+        let report = explain_encoding(&PopApiError::UseCase(UseCaseError::Fungibles(
+            FungiblesError::InsufficientBalance,
+        )));
+        assert_eq!(
+            report.to_string(),
+            "[14] UseCase | [0] Fungibles | [3] InsufficientBalance"
+        );
+    }
+
+    #[test]
+    fn test_struct_variant() {
+        let report = explain_encoding(&PopApiError::Unspecified {
+            dispatch_error_index: 3,
+            error_index: 2,
+            error: 1,
+        });
+        assert_eq!(
+            report.to_string(),
+            "[15] Unspecified | [3] dispatch_error_index: 3 | [2] error_index: 2 | [1] error: 1"
+        );
+        let report = explain_encoding(&PopApiError::Module(ModuleError { index: 1, error: 2 }));
+        assert_eq!(
+            report.to_string(),
+            "[3] Module | [1] index: 1 | [2] error: 2"
+        );
+    }
+
+    #[test]
+    fn test_vec_length_prefix() {
+        let report = explain_encoding(&vec![1u16, 2, 3]);
+        assert_eq!(
+            report.to_string(),
+            "[12] length: 3 | [1, 0] u16: 1 | [2, 0] u16: 2 | [3, 0] u16: 3"
+        );
+        assert_eq!(
+            report.segments.iter().map(|s| s.bytes.len()).sum::<usize>(),
+            vec![1u16, 2, 3].encoded_size()
+        );
+    }
+
+    #[test]
+    fn test_pop_api_error_layout() {
+        let layout = variant_layout::<PopApiError>();
+        assert_eq!(layout.len(), 16);
+        assert!(layout.iter().all(|variant| variant.max_len <= Some(4)));
+        let use_case = layout
+            .iter()
+            .find(|variant| variant.name == "UseCase")
+            .unwrap();
+        assert_eq!(
+            (use_case.index, use_case.min_len, use_case.max_len),
+            (14, 3, Some(4))
+        );
+        let other = &layout[0];
+        assert_eq!((other.name.as_str(), other.max_len), ("Other", Some(2)));
+        assert!(fits_status_budget::<PopApiError>(4));
+        assert!(!fits_status_budget::<PopApiError>(3));
+        assert_eq!(check_budget::<PopApiError>(4), Ok(()));
+        assert_eq!(max_nesting_depth::<PopApiError>(), 3);
+    }
+
+    #[test]
+    fn test_use_case_error_layout() {
+        // Inside `PopApiError::UseCase` one more byte is needed, i.e. 4.
+        assert_eq!(
+            variant_layout::<UseCaseError>(),
+            [
+                VariantLayout {
+                    name: "Fungibles".into(),
+                    index: 0,
+                    min_len: 2,
+                    max_len: Some(2),
+                },
+                VariantLayout {
+                    name: "Module".into(),
+                    index: 1,
+                    min_len: 3,
+                    max_len: Some(3),
+                }
+            ]
+        );
+        assert!(fits_status_budget::<UseCaseError>(3));
+        assert!(!fits_status_budget::<UseCaseError>(2));
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum Synthetic {
+        Unit,
+        Bytes(Vec<u8>),
+        Pair { a: u8, b: Compact<u32> },
+    }
+
+    #[test]
+    fn test_unbounded_layout() {
+        let layout = variant_layout::<Synthetic>();
+        let lens: Vec<_> = layout
+            .iter()
+            .map(|variant| (variant.min_len, variant.max_len))
+            .collect();
+        assert_eq!(lens, [(1, Some(1)), (2, None), (3, Some(7))]);
+        assert!(!fits_status_budget::<Synthetic>(4));
+        assert_eq!(variant_layout::<ModuleError>(), []);
+        assert!(fits_status_budget::<ModuleError>(2));
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum Outer {
+        Unit,
+        Nested(First),
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum First {
+        Nested(Second),
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum Second {
+        Nested(Third),
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum Third {
+        Nested(Fourth),
+    }
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    enum Fourth {
+        Leaf,
+    }
+
+    #[test]
+    fn test_check_budget() {
+        assert_eq!(max_nesting_depth::<Outer>(), 5);
+        assert_eq!(check_budget::<Outer>(5), Ok(()));
+        let report = check_budget::<Outer>(4).unwrap_err();
+        assert_eq!(
+            report.path,
+            [
+                "Outer::Nested",
+                "First::Nested",
+                "Second::Nested",
+                "Third::Nested",
+                "Fourth::Leaf"
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "`Outer::Nested -> First::Nested -> Second::Nested -> Third::Nested -> Fourth::Leaf` \
+             encodes to up to 5 bytes, exceeding the budget of 4 bytes"
+        );
+    }
+
+    #[test]
+    fn test_check_budget_unbounded() {
+        let report = check_budget::<Synthetic>(4).unwrap_err();
+        assert_eq!(report.max_len, None);
+        assert_eq!(report.path, ["Synthetic::Bytes"]);
+        let report = check_budget::<ModuleError>(1).unwrap_err();
+        assert_eq!(
+            (report.max_len, report.path),
+            (Some(2), vec!["index".into()])
+        );
+        assert_eq!(max_nesting_depth::<ModuleError>(), 0);
+    }
+}
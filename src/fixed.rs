@@ -0,0 +1,199 @@
+// Fixed size encodings, e.g. for status codes, event fields or storage keys
+// that always occupy the same number of bytes.
+use crate::encode_to_output;
+use core::fmt;
+use parity_scale_codec::{Decode, Encode, Error, Input, MaxEncodedLen, Output};
+
+// Wraps a value so that it always encodes to exactly `N` bytes: its SCALE
+// encoding followed by zero padding. Encoding panics if the encoding of the
+// value is longer than `N` bytes, as `Encode` can't fail; use a type whose
+// `MaxEncodedLen` fits. Decoding consumes exactly `N` bytes and rejects
+// non-zero padding.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FixedEncoded<T, const N: usize>(pub T);
+
+impl<T: Encode, const N: usize> Encode for FixedEncoded<T, N> {
+    fn size_hint(&self) -> usize {
+        N
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        let output = encode_to_output::<N, T>(&self.0);
+        assert!(
+            output.len <= N,
+            "encoding of {} bytes exceeds the fixed size of {N} bytes",
+            output.len
+        );
+        dest.write(&output.bytes);
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for FixedEncoded<T, N> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let mut bytes = [0u8; N];
+        input.read(&mut bytes)?;
+        let mut remaining = &bytes[..];
+        let value = T::decode(&mut remaining)?;
+        if remaining.iter().any(|byte| *byte != 0) {
+            return Err("Fixed size encoding has non-zero padding".into());
+        }
+        Ok(Self(value))
+    }
+}
+
+impl<T: Encode, const N: usize> MaxEncodedLen for FixedEncoded<T, N> {
+    fn max_encoded_len() -> usize {
+        N
+    }
+}
+
+// An optional byte encoded in a single byte rather than the two of
+// `Option<u8>`, with `OptionByte::SENTINEL` standing in for `None`. Meant for
+// optional payload bytes of errors whose values never reach the sentinel, e.g.
+// an index into a short list, where the extra byte of `Option<u8>` would exceed
+// the budget of a status code. Not suited for raw bytes of other types, e.g.
+// the error byte of a `ModuleError`, where every value is valid, nor for
+// mirrors of polkadot sdk types, whose encoding has to match.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct OptionByte(Option<u8>);
+
+impl OptionByte {
+    // The encoding of `None`.
+    pub const SENTINEL: u8 = u8::MAX;
+    pub const NONE: Self = Self(None);
+
+    pub fn get(&self) -> Option<u8> {
+        self.0
+    }
+}
+
+// The sentinel can't be represented as a value, it would decode to `None`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SentinelCollision;
+
+impl fmt::Display for SentinelCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is reserved for `None` and can't be represented",
+            OptionByte::SENTINEL
+        )
+    }
+}
+
+impl TryFrom<Option<u8>> for OptionByte {
+    type Error = SentinelCollision;
+
+    fn try_from(value: Option<u8>) -> Result<Self, Self::Error> {
+        match value {
+            Some(Self::SENTINEL) => Err(SentinelCollision),
+            value => Ok(Self(value)),
+        }
+    }
+}
+
+impl From<OptionByte> for Option<u8> {
+    fn from(value: OptionByte) -> Self {
+        value.0
+    }
+}
+
+impl Encode for OptionByte {
+    fn size_hint(&self) -> usize {
+        1
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        dest.push_byte(self.0.unwrap_or(Self::SENTINEL));
+    }
+}
+
+// Every byte decodes, the sentinel to `None`.
+impl Decode for OptionByte {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        Ok(match input.read_byte()? {
+            Self::SENTINEL => Self::NONE,
+            byte => Self(Some(byte)),
+        })
+    }
+}
+
+impl MaxEncodedLen for OptionByte {
+    fn max_encoded_len() -> usize {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_encoding_stable, FungiblesError, ModuleError, PopApiError, UseCaseError};
+
+    #[test]
+    fn test_exact_fit() {
+        let error = FixedEncoded::<_, 2>(ModuleError { index: 1, error: 2 });
+        assert_encoding_stable!(error, [1, 2]);
+        assert_eq!(FixedEncoded::decode(&mut &[1, 2][..]), Ok(error));
+    }
+
+    #[test]
+    fn test_padded() {
+        let error = FixedEncoded::<_, 4>(PopApiError::BadOrigin);
+        assert_encoding_stable!(error, hex "02000000");
+        assert_eq!(FixedEncoded::decode(&mut &[2, 0, 0, 0][..]), Ok(error));
+        assert!(FixedEncoded::<PopApiError, 4>::decode(&mut &[2, 0, 0, 1][..]).is_err());
+        // Exactly `N` bytes are required.
+        assert!(FixedEncoded::<PopApiError, 4>::decode(&mut &[2, 0][..]).is_err());
+        assert_eq!(FixedEncoded::<PopApiError, 4>::max_encoded_len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding of 8 bytes exceeds the fixed size of 4 bytes")]
+    fn test_over_long() {
+        FixedEncoded::<_, 4>(u64::MAX).encode();
+    }
+
+    #[test]
+    fn test_nested() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        let value = (7u8, FixedEncoded::<_, 4>(error), 9u8);
+        let encoded = value.encode();
+        assert_encoding_stable!(value, [7, 14, 0, 6, 0, 9]);
+        let mut input = &encoded[..];
+        assert_eq!(Decode::decode(&mut input), Ok(value));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_option_byte_round_trip() {
+        for value in (0..OptionByte::SENTINEL).map(Some).chain([None]) {
+            let option = OptionByte::try_from(value).unwrap();
+            let encoded = option.encode();
+            assert_eq!(encoded, [value.unwrap_or(OptionByte::SENTINEL)]);
+            assert_eq!(OptionByte::decode(&mut &encoded[..]), Ok(option));
+            assert_eq!(Option::<u8>::from(option), value);
+        }
+        assert_eq!(OptionByte::max_encoded_len(), 1);
+        // One byte less than `Option<u8>`.
+        assert_eq!(Some(7u8).encode(), [1, 7]);
+        assert_encoding_stable!(OptionByte::try_from(Some(7)).unwrap(), [7]);
+    }
+
+    #[test]
+    fn test_option_byte_sentinel() {
+        assert_eq!(
+            OptionByte::try_from(Some(OptionByte::SENTINEL)),
+            Err(SentinelCollision)
+        );
+        assert_eq!(
+            SentinelCollision.to_string(),
+            "255 is reserved for `None` and can't be represented"
+        );
+        assert_eq!(
+            OptionByte::decode(&mut &[u8::MAX][..]),
+            Ok(OptionByte::NONE)
+        );
+        assert_eq!(OptionByte::default().get(), None);
+        assert!(OptionByte::decode(&mut &[][..]).is_err());
+    }
+}
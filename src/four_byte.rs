@@ -0,0 +1,114 @@
+// Conversion of the error enums to and from status codes through a single
+// trait, so that a use case error can be converted on its own, e.g. a
+// `FungiblesError` received from a use case specific API. Every implementation
+// fits four bytes, which `scale_layout_test` guarantees. The trait is sealed:
+// only error enums of this crate are known to fit and to decode strictly. The
+// methods aren't named `to_u32` and `from_u32` so that they don't clash with
+// `ErrorCode::to_u32` when both traits are in scope.
+use crate::{
+    decode_padded, encode_to_output, try_decode_from_u32, DecodeError, FungiblesError, PopApiError,
+    UseCaseError,
+};
+use parity_scale_codec::{Decode, Encode};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::PopApiError {}
+    impl Sealed for crate::UseCaseError {}
+    impl Sealed for crate::FungiblesError {}
+}
+
+pub trait FourByteCodec: Encode + Decode + sealed::Sealed {
+    // The encoded error, zero padded to four bytes and interpreted as a little
+    // endian `u32`.
+    fn to_status_code(&self) -> u32 {
+        u32::from_le_bytes(encode_to_output::<4, Self>(self).bytes)
+    }
+
+    // Strictly decodes a code created by `to_status_code`, the bytes following the
+    // encoded error must be zero padding.
+    fn from_status_code(code: u32) -> Result<Self, DecodeError>
+    where
+        Self: Sized,
+    {
+        decode_padded(code.to_le_bytes(), |input| Self::decode(input))
+    }
+}
+
+// The status code, encoded by hand rather than by the derived `Encode`.
+impl FourByteCodec for PopApiError {
+    fn to_status_code(&self) -> u32 {
+        u32::from(*self)
+    }
+
+    fn from_status_code(code: u32) -> Result<Self, DecodeError> {
+        try_decode_from_u32(code)
+    }
+}
+
+impl FourByteCodec for UseCaseError {}
+
+impl FourByteCodec for FungiblesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{all_valid_codes, ErrorCode, ModuleError};
+
+    #[test]
+    fn test_pop_api_error() {
+        for code in all_valid_codes() {
+            let error = PopApiError::from_status_code(code).unwrap();
+            assert_eq!(error.to_status_code(), code);
+            assert_eq!(FourByteCodec::to_status_code(&error), u32::from(error));
+        }
+        assert_eq!(
+            PopApiError::from_status_code(u32::from_le_bytes([2, 0, 0, 1])),
+            try_decode_from_u32(u32::from_le_bytes([2, 0, 0, 1]))
+        );
+    }
+
+    #[test]
+    fn test_fungibles_error() {
+        for error in FungiblesError::all() {
+            let code = error.to_status_code();
+            assert_eq!(code, u32::from(error.codec_index()));
+            assert_eq!(FungiblesError::from_status_code(code), Ok(*error));
+        }
+        let error = FungiblesError::from_status_code(u32::MAX).unwrap_err();
+        assert_eq!(error.position, 0);
+        // `NoAccount` followed by non-zero padding.
+        let error = FungiblesError::from_status_code(u32::from_le_bytes([6, 0, 1, 0])).unwrap_err();
+        assert_eq!(error.position, 2);
+        assert_eq!(error.error.to_string(), "Status code has non-zero padding");
+    }
+
+    // A use case error converts to the bytes following the `UseCase` index.
+    #[test]
+    fn test_use_case_error() {
+        let errors = [
+            UseCaseError::Fungibles(FungiblesError::InUse),
+            UseCaseError::Module(ModuleError {
+                index: 150,
+                error: 3,
+            }),
+        ];
+        for error in errors {
+            let code = error.to_status_code();
+            assert_eq!(UseCaseError::from_status_code(code), Ok(error));
+            let status_code = u32::from(PopApiError::UseCase(error));
+            assert_eq!(code.to_le_bytes()[..3], status_code.to_le_bytes()[1..]);
+        }
+    }
+
+    // With both traits in scope, e.g. through `use encoding::*`, `to_u32` is
+    // unambiguous and agrees with `to_status_code`.
+    #[test]
+    fn test_error_code_in_scope() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        assert_eq!(error.to_u32(), Ok(error.to_status_code()));
+        let error = FungiblesError::NoAccount;
+        assert_eq!(error.to_u32(), Ok(error.to_status_code()));
+    }
+}
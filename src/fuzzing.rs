@@ -0,0 +1,101 @@
+// Generation of arbitrary `DispatchError`s and the invariants of their
+// conversion, shared by the fuzz targets and the property tests.
+use crate::PopApiError;
+use arbitrary::Unstructured;
+use parity_scale_codec::Encode;
+use sp_runtime::{ArithmeticError, DispatchError, ModuleError, TokenError, TransactionalError};
+
+const OTHER_MESSAGES: [&str; 4] = ["DecodingFailed", "UnknownFunctionId", "Unregistered", ""];
+const TOKEN_ERRORS: [TokenError; 10] = [
+    TokenError::FundsUnavailable,
+    TokenError::OnlyProvider,
+    TokenError::BelowMinimum,
+    TokenError::CannotCreate,
+    TokenError::UnknownAsset,
+    TokenError::Frozen,
+    TokenError::Unsupported,
+    TokenError::CannotCreateHold,
+    TokenError::NotExpendable,
+    TokenError::Blocked,
+];
+const ARITHMETIC_ERRORS: [ArithmeticError; 3] = [
+    ArithmeticError::Underflow,
+    ArithmeticError::Overflow,
+    ArithmeticError::DivisionByZero,
+];
+const TRANSACTIONAL_ERRORS: [TransactionalError; 2] = [
+    TransactionalError::LimitReached,
+    TransactionalError::NoLayer,
+];
+
+// Generates a `DispatchError` out of unstructured (fuzzer provided) bytes,
+// covering every variant with arbitrary module indices and error bytes.
+pub fn arbitrary_dispatch_error(u: &mut Unstructured) -> arbitrary::Result<DispatchError> {
+    Ok(match u.int_in_range(0..=13)? {
+        0 => DispatchError::Other(u.choose(&OTHER_MESSAGES)?),
+        1 => DispatchError::CannotLookup,
+        2 => DispatchError::BadOrigin,
+        3 => DispatchError::Module(ModuleError {
+            index: u.arbitrary()?,
+            error: u.arbitrary()?,
+            message: None,
+        }),
+        4 => DispatchError::ConsumerRemaining,
+        5 => DispatchError::NoProviders,
+        6 => DispatchError::TooManyConsumers,
+        7 => DispatchError::Token(*u.choose(&TOKEN_ERRORS)?),
+        8 => DispatchError::Arithmetic(*u.choose(&ARITHMETIC_ERRORS)?),
+        9 => DispatchError::Transactional(*u.choose(&TRANSACTIONAL_ERRORS)?),
+        10 => DispatchError::Exhausted,
+        11 => DispatchError::Corruption,
+        12 => DispatchError::Unavailable,
+        _ => DispatchError::RootNotAllowed,
+    })
+}
+
+// Panics if the conversion of the error breaks one of its invariants: the
+// converted error has to fit the four byte status code and converting the same
+// error twice has to result in the same error.
+pub fn check_conversion(error: DispatchError) {
+    let converted = PopApiError::from(error);
+    assert!(
+        converted.encoded_size() <= 4,
+        "{error:?} converts to {converted:?} which doesn't fit a status code"
+    );
+    assert_eq!(
+        converted,
+        PopApiError::from(error),
+        "conversion of {error:?} is not deterministic"
+    );
+}
+
+// Runs the invariant check for the error generated from the given bytes, bytes
+// that don't generate an error are ignored.
+pub fn check_conversion_from_bytes(data: &[u8]) {
+    if let Ok(error) = arbitrary_dispatch_error(&mut Unstructured::new(data)) {
+        check_conversion(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn conversion_invariants(data in proptest::collection::vec(any::<u8>(), 0..16)) {
+            check_conversion_from_bytes(&data);
+        }
+    }
+
+    // Inputs found by the fuzzer are added to `fuzz/regressions/conversion` and
+    // replayed here.
+    #[test]
+    fn test_conversion_regressions() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/regressions/conversion");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            check_conversion_from_bytes(&std::fs::read(entry.unwrap().path()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Interpretations of a bare status code without context, e.g. a number
+// reported to support, under every decoding scheme, most plausible first.
+//
+// There is no legacy layout or versioned packing scheme: the layout of status
+// codes has only ever grown by appending variants, so every code of a released
+// version decodes strictly to the same error today, which `tests/compat.rs`
+// checks against the fixtures of every release. The prototype this crate
+// started from had placeholder nested errors, e.g. a single `TokenError`
+// variant, but never produced codes outside of its tests. Lenient decoding is
+// what that prototype did, it is the only other scheme codes were produced
+// with.
+use crate::{codec_compat, try_decode_from_u32, PopApiError};
+
+// How a code was decoded.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Scheme {
+    /// The status code of the current layout, with zero padding.
+    Strict,
+    /// The current layout, ignoring the bytes following the error, as the
+    /// original `encode_and_decode_to_pop_api_error` helper did.
+    Lenient,
+}
+
+impl Scheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheme::Strict => "strict",
+            Scheme::Lenient => "lenient",
+        }
+    }
+}
+
+// An interpretation of a code.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Guess {
+    pub error: PopApiError,
+    pub scheme: Scheme,
+    // How much to trust the interpretation.
+    pub note: &'static str,
+}
+
+// Every successful interpretation of the code, strict first. A code that
+// decodes under no scheme results in no guesses.
+pub fn guess_decode(code: u32) -> Vec<Guess> {
+    let mut guesses = Vec::new();
+    if let Ok(error) = try_decode_from_u32(code) {
+        guesses.push(Guess {
+            error,
+            scheme: Scheme::Strict,
+            note: "a valid status code",
+        });
+    }
+    let bytes = code.to_le_bytes();
+    let mut input = &bytes[..];
+    if let Ok(error) = codec_compat::decode(&mut input) {
+        let note = if input.iter().all(|byte| *byte == 0) {
+            "same as strict, the padding is zero"
+        } else {
+            "non-zero bytes follow the error: a corrupted code or a buggy encoder"
+        };
+        guesses.push(Guess {
+            error,
+            scheme: Scheme::Lenient,
+            note,
+        });
+    }
+    guesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError, UseCaseError};
+
+    #[test]
+    fn test_valid_code() {
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        let guesses = guess_decode(u32::from(error));
+        let schemes: Vec<_> = guesses.iter().map(|guess| guess.scheme).collect();
+        assert_eq!(schemes, [Scheme::Strict, Scheme::Lenient]);
+        assert!(guesses.iter().all(|guess| guess.error == error));
+    }
+
+    #[test]
+    fn test_lenient_only() {
+        // `BadOrigin` followed by garbage.
+        let guesses = guess_decode(u32::from_le_bytes([2, 0, 0, 1]));
+        assert_eq!(
+            guesses,
+            [Guess {
+                error: PopApiError::BadOrigin,
+                scheme: Scheme::Lenient,
+                note: "non-zero bytes follow the error: a corrupted code or a buggy encoder",
+            }]
+        );
+        assert_eq!(guesses[0].scheme.name(), "lenient");
+    }
+
+    #[test]
+    fn test_garbage() {
+        assert!(guess_decode(u32::MAX).is_empty());
+        assert!(guess_decode(u32::from_le_bytes([14, 0, 10, 0])).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+// The SCALE encoding of a value as hex, e.g. for debugging or for passing
+// values on the command line.
+use core::{fmt, str::FromStr};
+use parity_scale_codec::{Decode, DecodeAll, Encode, Error};
+
+// Displays as the `0x` prefixed hex of the value's encoding, e.g. `0x0e0003`
+// for `UseCase(Fungibles(InsufficientBalance))`, and parses from it. Parsing
+// is strict: the prefix is optional, but the digits must be complete and
+// decode to exactly one value without trailing bytes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ScaleHex<T>(pub T);
+
+impl<T: Encode> fmt::Display for ScaleHex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        for byte in self.0.encode() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Decode> FromStr for ScaleHex<T> {
+    type Err = Error;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let bytes = decode_hex(hex.strip_prefix("0x").unwrap_or(hex))?;
+        T::decode_all(&mut &bytes[..]).map(ScaleHex)
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("Hex has an odd number of digits".into());
+    }
+    // Digit by digit, `u8::from_str_radix` would accept a sign, e.g. `+2`.
+    let digit = |digit: u8| char::from(digit).to_digit(16).map(|value| value as u8);
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            digit(pair[0])
+                .zip(digit(pair[1]))
+                .map(|(high, low)| high << 4 | low)
+                .ok_or_else(|| "Hex contains an invalid digit".into())
+        })
+        .collect()
+}
+
+#[cfg(feature = "serde")]
+impl<T: Encode> serde::Serialize for ScaleHex<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Decode> serde::Deserialize<'de> for ScaleHex<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError, ModuleError, PopApiError, UseCaseError};
+
+    fn round_trip<T: Encode + Decode + PartialEq + fmt::Debug>(value: T, hex: &str) {
+        let displayed = ScaleHex(&value).to_string();
+        assert_eq!(displayed, hex);
+        assert_eq!(
+            displayed.parse::<ScaleHex<T>>().map(|value| value.0),
+            Ok(value)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+            "0x0e0003",
+        );
+        round_trip(PopApiError::BadOrigin, "0x02");
+        round_trip(
+            ModuleError {
+                index: 52,
+                error: 255,
+            },
+            "0x34ff",
+        );
+        round_trip((7u8, vec![1u16, 2]), "0x070801000200");
+    }
+
+    #[test]
+    fn test_prefix_is_optional() {
+        assert_eq!("02".parse(), Ok(ScaleHex(PopApiError::BadOrigin)));
+        assert_eq!("0x02".parse(), Ok(ScaleHex(PopApiError::BadOrigin)));
+    }
+
+    #[test]
+    fn test_malformed_hex() {
+        let error = "0x0g".parse::<ScaleHex<PopApiError>>().unwrap_err();
+        assert_eq!(error.to_string(), "Hex contains an invalid digit");
+        let error = "0x002".parse::<ScaleHex<PopApiError>>().unwrap_err();
+        assert_eq!(error.to_string(), "Hex has an odd number of digits");
+        assert!("0xé0".parse::<ScaleHex<PopApiError>>().is_err());
+        // Signs aren't digits.
+        for hex in ["0x+2", "+0+0", "+1+2"] {
+            let error = hex.parse::<ScaleHex<PopApiError>>().unwrap_err();
+            assert_eq!(error.to_string(), "Hex contains an invalid digit", "{hex}");
+        }
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        assert!("0x0200".parse::<ScaleHex<PopApiError>>().is_err());
+        // Too short is rejected as well.
+        assert!("0x03".parse::<ScaleHex<PopApiError>>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_as_hex_string() {
+        let value = ScaleHex(ModuleError { index: 1, error: 2 });
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"0x0102\"");
+        assert_eq!(serde_json::from_str(&json).ok(), Some(value));
+        assert!(serde_json::from_str::<ScaleHex<ModuleError>>("\"0x01\"").is_err());
+    }
+}
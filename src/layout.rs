@@ -0,0 +1,196 @@
+// A fingerprint of the wire format of `PopApiError`, so that a runtime and a
+// contract can check that they agree on it, e.g. when the contract is deployed
+// or the runtime upgraded. The runtime exposes `layout_hash()`, e.g. through a
+// runtime API or a chain extension function returning the 32 bytes, and the
+// contract compares it with the `LAYOUT_HASH` of the version of this crate it
+// was built with, refusing to interpret status codes on a mismatch.
+use crate::explain::{registry_of, resolve};
+use crate::PopApiError;
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use scale_info::{
+    form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive, TypeInfo,
+};
+
+// The layout of `PopApiError` and every type reachable from it, written out
+// structurally, e.g. `enum { 0: (u8), 1: (), .. }`. Only what reaches the wire
+// is part of it: variant indices and the shape of the payloads, in index
+// order. Names are not, renaming a variant or field doesn't change the
+// layout. This is what `layout_hash()` hashes, exposed for auditing a hash.
+pub fn canonical_layout() -> String {
+    canonical_layout_of::<PopApiError>()
+}
+
+fn canonical_layout_of<T: TypeInfo + 'static>() -> String {
+    let (registry, id) = registry_of::<T>();
+    let mut layout = String::new();
+    write_type(&registry, id, &mut layout);
+    layout
+}
+
+// The BLAKE2b-256 hash of `canonical_layout()`. It only depends on the wire
+// format, so it is the same for every compilation and platform.
+pub fn layout_hash() -> [u8; 32] {
+    Blake2b::<U32>::digest(canonical_layout()).into()
+}
+
+fn write_type(registry: &PortableRegistry, id: u32, layout: &mut String) {
+    match &resolve(registry, id).type_def {
+        TypeDef::Composite(composite) => write_fields(registry, &composite.fields, layout),
+        TypeDef::Variant(variants) => {
+            let mut variants: Vec<_> = variants.variants.iter().collect();
+            variants.sort_by_key(|variant| variant.index);
+            layout.push_str("enum {");
+            for (i, variant) in variants.into_iter().enumerate() {
+                if i > 0 {
+                    layout.push(',');
+                }
+                layout.push_str(&format!(" {}: ", variant.index));
+                write_fields(registry, &variant.fields, layout);
+            }
+            layout.push_str(" }");
+        }
+        TypeDef::Sequence(sequence) => {
+            layout.push('[');
+            write_type(registry, sequence.type_param.id, layout);
+            layout.push(']');
+        }
+        TypeDef::Array(array) => {
+            layout.push('[');
+            write_type(registry, array.type_param.id, layout);
+            layout.push_str(&format!("; {}]", array.len));
+        }
+        TypeDef::Tuple(tuple) => {
+            layout.push('(');
+            for (i, field) in tuple.fields.iter().enumerate() {
+                if i > 0 {
+                    layout.push_str(", ");
+                }
+                write_type(registry, field.id, layout);
+            }
+            layout.push(')');
+        }
+        TypeDef::Compact(compact) => {
+            layout.push_str("compact ");
+            write_type(registry, compact.type_param.id, layout);
+        }
+        TypeDef::Primitive(primitive) => layout.push_str(primitive_name(primitive)),
+        TypeDef::BitSequence(bits) => {
+            layout.push_str("bits<");
+            write_type(registry, bits.bit_store_type.id, layout);
+            layout.push_str(", ");
+            write_type(registry, bits.bit_order_type.id, layout);
+            layout.push('>');
+        }
+    }
+}
+
+// Fields are encoded in order, named or not.
+fn write_fields(registry: &PortableRegistry, fields: &[Field<PortableForm>], layout: &mut String) {
+    layout.push('(');
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            layout.push_str(", ");
+        }
+        write_type(registry, field.ty.id, layout);
+    }
+    layout.push(')');
+}
+
+fn primitive_name(primitive: &TypeDefPrimitive) -> &'static str {
+    use TypeDefPrimitive::*;
+    match primitive {
+        Bool => "bool",
+        Char => "char",
+        Str => "str",
+        U8 => "u8",
+        U16 => "u16",
+        U32 => "u32",
+        U64 => "u64",
+        U128 => "u128",
+        U256 => "u256",
+        I8 => "i8",
+        I16 => "i16",
+        I32 => "i32",
+        I64 => "i64",
+        I128 => "i128",
+        I256 => "i256",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LAYOUT_HASH;
+
+    #[test]
+    fn test_canonical_layout() {
+        assert_eq!(
+            canonical_layout(),
+            "enum { 0: (u8), 1: (), 2: (), 3: ((u8, u8)), 4: (), 5: (), 6: (), \
+             7: (enum { 0: (), 1: (), 2: (), 3: (), 4: (), 5: (), 6: (), 7: (), 8: (), 9: () }), \
+             8: (enum { 0: (), 1: (), 2: () }), 9: (enum { 0: (), 1: () }), \
+             10: (), 11: (), 12: (), 13: (), \
+             14: (enum { 0: (enum { 0: (), 1: (), 2: (), 3: (), 4: (), 5: (), 6: (), 7: (), 8: (), \
+             9: () }), 1: ((u8, u8)) }), 15: (u8, u8, u8) }"
+        );
+    }
+
+    mod before {
+        #[derive(scale_info::TypeInfo)]
+        #[allow(dead_code)]
+        pub enum Error {
+            Unit,
+            Payload { index: u8, bytes: Vec<u8> },
+        }
+    }
+
+    // Same wire format, declared differently.
+    mod renamed {
+        #[derive(scale_info::TypeInfo)]
+        #[allow(dead_code)]
+        pub enum Failure {
+            #[codec(index = 1)]
+            Data(u8, Vec<u8>),
+            #[codec(index = 0)]
+            Empty,
+        }
+    }
+
+    mod reindexed {
+        #[derive(scale_info::TypeInfo)]
+        #[allow(dead_code)]
+        pub enum Error {
+            Unit,
+            #[codec(index = 2)]
+            Payload {
+                index: u8,
+                bytes: Vec<u8>,
+            },
+        }
+    }
+
+    mod changed_payload {
+        #[derive(scale_info::TypeInfo)]
+        #[allow(dead_code)]
+        pub enum Error {
+            Unit,
+            Payload { index: u16, bytes: Vec<u8> },
+        }
+    }
+
+    #[test]
+    fn test_only_the_wire_format_counts() {
+        let layout = canonical_layout_of::<before::Error>();
+        assert_eq!(layout, "enum { 0: (), 1: (u8, [u8]) }");
+        assert_eq!(canonical_layout_of::<renamed::Failure>(), layout);
+        assert_ne!(canonical_layout_of::<reindexed::Error>(), layout);
+        assert_ne!(canonical_layout_of::<changed_payload::Error>(), layout);
+    }
+
+    // A changed hash means a changed wire format: review the change and update
+    // `LAYOUT_HASH` deliberately.
+    #[test]
+    fn test_layout_hash_is_pinned() {
+        assert_eq!(layout_hash(), LAYOUT_HASH);
+    }
+}
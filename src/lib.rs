@@ -0,0 +1,1905 @@
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
+use parity_scale_codec::{Decode, Encode, Input, MaxEncodedLen, Output};
+#[cfg(any(test, feature = "explain"))]
+use scale_info::TypeInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[macro_use]
+mod macros;
+
+mod catalogue;
+mod chunked;
+mod classification;
+mod codec_compat;
+pub mod codes;
+mod compact;
+mod compact_status;
+#[cfg(any(all(test, feature = "runtime"), feature = "test-utils"))]
+pub mod conformance;
+#[cfg(feature = "runtime")]
+mod conversion;
+#[cfg(feature = "explain")]
+mod diff;
+mod dispatch;
+#[cfg(feature = "explain")]
+mod dynamic;
+#[cfg(feature = "explain")]
+mod explain;
+mod fixed;
+mod four_byte;
+#[cfg(any(all(test, feature = "runtime"), feature = "fuzzing"))]
+pub mod fuzzing;
+mod guess;
+mod hex;
+#[cfg(feature = "explain")]
+mod layout;
+#[cfg(feature = "sol")]
+mod sol;
+#[doc(hidden)]
+pub mod stable;
+mod telemetry;
+pub mod tour;
+pub use catalogue::{conformance_fixtures, readable_table};
+pub use chunked::ChunkedInput;
+pub use classification::{ErrorOrigin, PermissionKind, UserAction};
+pub use compact::{compact_savings, compare_compact};
+pub use compact_status::CompactStatus;
+#[cfg(feature = "runtime")]
+pub use conversion::{extract_dispatch_error, Converter, DefaultConverter};
+#[cfg(feature = "explain")]
+pub use diff::{diff_registries, RegistryDiff, VariantChange};
+pub use dispatch::{
+    decode_dispatch_error, fungibles_from_pallet_error, refine_approval_error, refine_module_error,
+    RawDispatchError, ASSETS_PALLET_INDEX, BALANCES_PALLET_INDEX, SYSTEM_PALLET_INDEX,
+};
+#[cfg(feature = "explain")]
+pub use dynamic::{decode_dynamic, DynError, Fields, Value};
+#[cfg(feature = "explain")]
+pub use explain::{
+    check_budget, explain_encoding, fits_status_budget, max_nesting_depth, variant_layout,
+    BudgetReport, EncodingReport, Segment, VariantLayout,
+};
+pub use fixed::{FixedEncoded, OptionByte, SentinelCollision};
+pub use four_byte::FourByteCodec;
+pub use guess::{guess_decode, Guess, Scheme};
+pub use hex::ScaleHex;
+#[cfg(feature = "explain")]
+pub use layout::{canonical_layout, layout_hash};
+pub use telemetry::{summarize_codes, ErrorCounter, ErrorReport};
+
+// Packs a contract's own errors into status codes, see `encoding-macros`.
+pub use encoding_macros::ToStatusCode;
+
+// Almost identical with the DispatchError
+// The PopApiError. The idea is that it majorily returns the `UseCase` error.
+// Conversion is handled on the runtime side so that new (or missed) errors,
+// coming from polkadot sdk upgrades can be handled via runtime upgrades. In
+// addition, all this conversion logic is now handled at the runtime in stead
+// of the contract which doesn't increase the size of the contract binary, aka
+// the PoV.
+//
+// With the `borsh` feature the error types can also be serialized with Borsh.
+// Note that Borsh is a separate representation: it doesn't know about codec
+// attributes and encodes collections differently than SCALE. The status code
+// is always the SCALE encoding.
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 4))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopApiError {
+    Other(u8),
+    CannotLookup,
+    BadOrigin,
+    // This is only returned if the error originates from a pallet and the
+    // conversion logic hasn't picked it up.
+    Module(ModuleError),
+    ConsumerRemaining,
+    NoProviders,
+    TooManyConsumers,
+    Token(TokenError),
+    Arithmetic(ArithmeticError),
+    Transactional(TransactionalError),
+    Exhausted,
+    Corruption,
+    Unavailable,
+    RootNotAllowed,
+    // This error is carefully defined based on the use case and the errors that
+    // we want to output to the developers.
+    UseCase(UseCaseError),
+    // This error is for deployed contracts that encounter a new error that
+    // wasn't in the sdk at the time of deployment. The pop api is upgradeable
+    // and can therefore convert that error in this error so that the contract
+    // maintainers are still able to figure out what the error is by looking at
+    // the provided info.
+    Unspecified {
+        // Index within the DispatchError
+        dispatch_error_index: u8,
+        // Index within the DispatchError variant. `0` if the above is nested.
+        error_index: u8,
+        // For struct variant with an index and error. `0` if the above is nested.
+        error: u8,
+    },
+}
+
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 3))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UseCaseError {
+    Fungibles(FungiblesError),
+    // A pallet error raised while dispatching a use case that has no use case
+    // error of its own, e.g. an error of a pallet called by the use case. The
+    // runtime returns this instead of the top-level `Module` when the call
+    // went through a use case, so that contracts can tell the error apart from
+    // a pallet error outside of any use case. The `DispatchError` alone
+    // doesn't carry that context, the default conversion therefore never
+    // produces it.
+    Module(ModuleError),
+    // NonFungibles(NonFungiblesError),
+    // etc
+}
+
+macros::define_errors! {
+    #[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 1))]
+    #[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+    #[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+    #[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub enum FungiblesError {
+        /// The asset is not live; either frozen or being destroyed.
+        ///
+        /// Whether the asset is frozen, which can be transient, or being
+        /// destroyed, which is terminal, is deliberately not encoded:
+        /// `pallet-assets` reports both as `AssetNotLive` without the state, so
+        /// the runtime can't fill it in. Callers needing it have to query the
+        /// asset's status.
+        AssetNotLive: index 0,
+            "the asset is not live; either frozen or being destroyed",
+            "The asset is frozen or being destroyed, operations on it are not possible.";
+        /// The amount to mint is less than the existential deposit.
+        BelowMinimum: index 1,
+            "the amount to mint is less than the existential deposit",
+            "The resulting balance would be below the minimum balance of the asset.";
+        /// Not enough allowance to fulfill a request is available.
+        InsufficientAllowance: index 2,
+            "not enough allowance to fulfill the request",
+            "The spender has not been approved to spend the requested amount on behalf of the owner.";
+        /// Not enough balance to fulfill a request is available.
+        InsufficientBalance: index 3,
+            "not enough balance to fulfill the request",
+            "The account does not hold enough of the asset to fulfill the request.";
+        /// The asset ID is already taken.
+        InUse: index 4,
+            "the asset ID is already taken",
+            "An asset with the given ID already exists, another ID has to be used.";
+        /// Minimum balance should be non-zero.
+        MinBalanceZero: index 5,
+            "minimum balance should be non-zero",
+            "An asset must be created with a minimum balance larger than zero.";
+        /// The account to alter does not exist.
+        NoAccount: index 6,
+            "the account to alter does not exist",
+            "The account does not hold the asset or does not exist.";
+        /// The signing account has no permission to do the operation.
+        NoPermission: index 7,
+            "the signing account has no permission to do the operation",
+            "The signing account is not allowed to perform the operation on the asset.";
+        /// The given asset ID is unknown.
+        Unknown: index 8,
+            "the given asset ID is unknown",
+            "No asset exists with the given ID.";
+        /// The allowance can't be increased, it would overflow.
+        CannotIncreaseAllowance: index 9,
+            "the allowance cannot be increased any further",
+            "Increasing the allowance by the amount would overflow it, a smaller amount has to be approved.";
+    }
+}
+
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 2))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModuleError {
+    // Pallet index.
+    pub index: u8,
+    // Error within the pallet's error, nested errors can not be further defined.
+    pub error: u8,
+}
+
+// The `TokenError`, `ArithmeticError` and `TransactionalError` mirror their
+// polkadot sdk counterparts variant for variant, see the `conversion` module.
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 1))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TokenError {
+    FundsUnavailable,
+    OnlyProvider,
+    BelowMinimum,
+    CannotCreate,
+    UnknownAsset,
+    Frozen,
+    Unsupported,
+    CannotCreateHold,
+    NotExpendable,
+    Blocked,
+}
+
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 1))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ArithmeticError {
+    Underflow,
+    Overflow,
+    DivisionByZero,
+}
+
+#[cfg_attr(test, encoding_macros::scale_layout_test(max_len = 1))]
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen)]
+#[cfg_attr(feature = "borsh", derive(BorshDeserialize, BorshSerialize))]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TransactionalError {
+    LimitReached,
+    NoLayer,
+}
+
+// Helper function to encode DispatchError to u32
+#[deprecated(note = "use `u32::from`, which doesn't allocate")]
+pub fn encode_and_decode_to_u32(error: PopApiError) -> u32 {
+    let mut encoded = error.encode();
+    encoded.resize(4, 0);
+    u32::decode(&mut &encoded[..]).unwrap()
+}
+
+// Helper function to decode DispatchError from u32. Panics if the code doesn't
+// decode and ignores non-zero padding.
+#[deprecated(note = "use `try_decode_from_u32`, which rejects invalid codes instead of panicking")]
+pub fn encode_and_decode_to_pop_api_error(value: u32) -> PopApiError {
+    codec_compat::decode(&mut &value.to_le_bytes()[..]).unwrap()
+}
+
+// `Output` encoding into a fixed size buffer on the stack, e.g. of four bytes
+// so that creating a status code doesn't allocate. Bytes beyond the buffer are
+// only counted.
+pub(crate) struct FixedOutput<const N: usize> {
+    pub(crate) bytes: [u8; N],
+    pub(crate) len: usize,
+}
+
+impl<const N: usize> Output for FixedOutput<N> {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            if let Some(slot) = self.bytes.get_mut(self.len) {
+                *slot = *byte;
+            }
+            self.len += 1;
+        }
+    }
+}
+
+pub(crate) fn encode_to_output<const N: usize, T: Encode>(value: &T) -> FixedOutput<N> {
+    let mut output = FixedOutput {
+        bytes: [0; N],
+        len: 0,
+    };
+    value.encode_to(&mut output);
+    output
+}
+
+impl PopApiError {
+    // The encoded error, zero padded to four bytes.
+    pub fn encode_to_array(&self) -> [u8; 4] {
+        // Every variant fits, which is guaranteed by the encoding tests.
+        let mut output = FixedOutput {
+            bytes: [0; 4],
+            len: 0,
+        };
+        codec_compat::encode_to(self, &mut output);
+        output.bytes
+    }
+}
+
+// The status code of an error is its SCALE encoding, zero padded to four bytes
+// and interpreted as a little endian `u32`.
+impl From<PopApiError> for u32 {
+    fn from(error: PopApiError) -> Self {
+        u32::from_le_bytes(error.encode_to_array())
+    }
+}
+
+// The status code of any type whose encoding fits four bytes: its SCALE
+// encoding, zero padded and interpreted as a little endian `u32`. Panics if the
+// maximum encoded length of the type exceeds four bytes.
+pub fn to_status_code<T: Encode + MaxEncodedLen>(value: &T) -> u32 {
+    assert!(
+        T::max_encoded_len() <= 4,
+        "encoding doesn't fit a status code"
+    );
+    u32::from_le_bytes(encode_to_output(value).bytes)
+}
+
+// Strictly decodes a status code created by `to_status_code`, the bytes
+// following the encoded value must be zero padding. Otherwise two different
+// codes would decode to the same value.
+pub fn from_status_code<T: Decode>(code: u32) -> Result<T, DecodeError> {
+    decode_padded(code.to_le_bytes(), |input| T::decode(input))
+}
+
+fn decode_padded<T>(
+    bytes: [u8; 4],
+    decode: impl FnOnce(&mut &[u8]) -> Result<T, parity_scale_codec::Error>,
+) -> Result<T, DecodeError> {
+    let mut input = &bytes[..];
+    // The decoder rejects the last byte it read, e.g. an unknown variant index.
+    let value = decode(&mut input).map_err(|error| DecodeError {
+        position: (bytes.len() - input.len()).saturating_sub(1),
+        error,
+    })?;
+    let consumed = bytes.len() - input.len();
+    if let Some(offset) = input.iter().position(|byte| *byte != 0) {
+        return Err(DecodeError {
+            position: consumed + offset,
+            error: "Status code has non-zero padding".into(),
+        });
+    }
+    Ok(value)
+}
+
+// A status code that doesn't decode, together with the position of the byte
+// that was rejected, e.g. 2 for an unknown fungibles error.
+#[derive(Debug, PartialEq)]
+pub struct DecodeError {
+    pub position: usize,
+    pub error: parity_scale_codec::Error,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (position: {})", self.error, self.position)
+    }
+}
+
+// For decoding that isn't only about a status code, the position is dropped.
+impl From<DecodeError> for parity_scale_codec::Error {
+    fn from(error: DecodeError) -> Self {
+        error.error
+    }
+}
+
+impl TryFrom<u32> for PopApiError {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        PopApiError::from_le_bytes(value.to_le_bytes())
+    }
+}
+
+// The status code of an error computed in const contexts, e.g. for static
+// tables or match arms. This re-implements the byte layout of the encoding,
+// `test_const_status_code` guarantees both never diverge.
+pub const fn const_status_code(error: PopApiError) -> u32 {
+    use PopApiError::*;
+    u32::from_le_bytes(match error {
+        Other(code) => [0, code, 0, 0],
+        CannotLookup => [1, 0, 0, 0],
+        BadOrigin => [2, 0, 0, 0],
+        Module(ModuleError { index, error }) => [3, index, error, 0],
+        ConsumerRemaining => [4, 0, 0, 0],
+        NoProviders => [5, 0, 0, 0],
+        TooManyConsumers => [6, 0, 0, 0],
+        // Variants of the mirrored polkadot sdk errors are encoded by their
+        // declaration order.
+        Token(error) => [7, error as u8, 0, 0],
+        Arithmetic(error) => [8, error as u8, 0, 0],
+        Transactional(error) => [9, error as u8, 0, 0],
+        Exhausted => [10, 0, 0, 0],
+        Corruption => [11, 0, 0, 0],
+        Unavailable => [12, 0, 0, 0],
+        RootNotAllowed => [13, 0, 0, 0],
+        UseCase(UseCaseError::Fungibles(error)) => [14, 0, error.codec_index(), 0],
+        UseCase(UseCaseError::Module(ModuleError { index, error })) => [14, 1, index, error],
+        Unspecified {
+            dispatch_error_index,
+            error_index,
+            error,
+        } => [15, dispatch_error_index, error_index, error],
+    })
+}
+
+// Strictly decodes a status code, the bytes following the encoded error must be
+// zero padding. Otherwise two different codes would decode to the same error.
+// Every code is an error: zero is a valid code decoding to `Other(0)`, it
+// doesn't mean "no error". See `decode_status` for a success code.
+pub fn try_decode_from_u32(value: u32) -> Result<PopApiError, DecodeError> {
+    PopApiError::from_le_bytes(value.to_le_bytes())
+}
+
+// The status code meaning "success" for `decode_status`. Zero can't be used as
+// it is `Other(0)`, instead the code has the discriminant 255, which will never
+// be assigned to an error and is therefore not reserved for future variants,
+// see `is_reserved_code`. The sentinel is fixed rather than configurable: the
+// runtime and every contract have to agree on it, and any other code either is
+// the code of an error or may become one with a future variant.
+pub const SUCCESS_CODE: u32 = u32::MAX;
+
+// The `layout_hash()` of this version of the crate, available without the
+// `explain` feature, e.g. for contracts comparing it with the hash exposed by
+// the runtime.
+pub const LAYOUT_HASH: [u8; 32] = [
+    0x5b, 0xb4, 0x20, 0xc5, 0x94, 0x20, 0x05, 0xbd, 0x4e, 0x90, 0x4e, 0x03, 0xed, 0x9a, 0x73, 0x3a,
+    0x49, 0xc7, 0xf7, 0xec, 0x43, 0x8e, 0xed, 0x1c, 0x18, 0x91, 0x14, 0x9c, 0x0b, 0x9c, 0xc0, 0x93,
+];
+
+// Decodes a status code that is either `SUCCESS_CODE`, decoding to `None`, or
+// an error.
+pub fn decode_status(value: u32) -> Result<Option<PopApiError>, DecodeError> {
+    if value == SUCCESS_CODE {
+        return Ok(None);
+    }
+    try_decode_from_u32(value).map(Some)
+}
+
+impl PopApiError {
+    // Strictly decodes the little endian bytes of a status code, directly from
+    // the array without allocating.
+    pub fn from_le_bytes(bytes: [u8; 4]) -> Result<Self, DecodeError> {
+        decode_padded(bytes, |input| codec_compat::decode(input))
+    }
+}
+
+// Decodes an error embedded in a larger message, returning the error and the
+// number of bytes it occupies, so that callers can advance past it. The error
+// is expected in its plain SCALE encoding, without padding.
+pub fn decode_counting(input: &[u8]) -> Result<(PopApiError, usize), parity_scale_codec::Error> {
+    let (error, remainder) = decode_with_remainder(input)?;
+    Ok((error, input.len() - remainder.len()))
+}
+
+// Decodes a value at the start of a larger buffer, e.g. an event body or a
+// return buffer with trailing data, returning the value and the bytes
+// following it. The input is borrowed immutably, so bytes consumed by a failed
+// decode aren't observable: on error, the caller still holds the full input.
+pub fn decode_with_remainder<T: Decode>(
+    input: &[u8],
+) -> Result<(T, &[u8]), parity_scale_codec::Error> {
+    let mut counting = CountingInput {
+        inner: input,
+        read: 0,
+    };
+    let value = T::decode(&mut counting)?;
+    Ok((value, &input[counting.read..]))
+}
+
+// `Input` counting the bytes read from the wrapped input.
+struct CountingInput<I> {
+    inner: I,
+    read: usize,
+}
+
+impl<I: Input> Input for CountingInput<I> {
+    fn remaining_len(&mut self) -> Result<Option<usize>, parity_scale_codec::Error> {
+        self.inner.remaining_len()
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), parity_scale_codec::Error> {
+        self.inner.read(into)?;
+        self.read += into.len();
+        Ok(())
+    }
+}
+
+// Decodes a status code in const contexts by matching its bytes directly,
+// bypassing the codec. Agrees with `try_decode_from_u32`, `None` if the code
+// is invalid.
+pub const fn fast_decode(value: u32) -> Option<PopApiError> {
+    use PopApiError::*;
+    // The inner enums are encoded by their declaration order, as is `all()`.
+    const fn nth<T: Copy>(all: &[T], index: u8) -> Option<T> {
+        if (index as usize) < all.len() {
+            Some(all[index as usize])
+        } else {
+            None
+        }
+    }
+    Some(match value.to_le_bytes() {
+        [0, code, 0, 0] => Other(code),
+        [1, 0, 0, 0] => CannotLookup,
+        [2, 0, 0, 0] => BadOrigin,
+        [3, index, error, 0] => Module(ModuleError { index, error }),
+        [4, 0, 0, 0] => ConsumerRemaining,
+        [5, 0, 0, 0] => NoProviders,
+        [6, 0, 0, 0] => TooManyConsumers,
+        [7, index, 0, 0] => match nth(TokenError::all(), index) {
+            Some(error) => Token(error),
+            None => return None,
+        },
+        [8, index, 0, 0] => match nth(ArithmeticError::all(), index) {
+            Some(error) => Arithmetic(error),
+            None => return None,
+        },
+        [9, index, 0, 0] => match nth(TransactionalError::all(), index) {
+            Some(error) => Transactional(error),
+            None => return None,
+        },
+        [10, 0, 0, 0] => Exhausted,
+        [11, 0, 0, 0] => Corruption,
+        [12, 0, 0, 0] => Unavailable,
+        [13, 0, 0, 0] => RootNotAllowed,
+        [14, 0, index, 0] => {
+            let all = FungiblesError::all();
+            let mut i = 0;
+            while i < all.len() && all[i].codec_index() != index {
+                i += 1;
+            }
+            if i == all.len() {
+                return None;
+            }
+            UseCase(UseCaseError::Fungibles(all[i]))
+        }
+        [14, 1, index, error] => UseCase(UseCaseError::Module(ModuleError { index, error })),
+        [15, dispatch_error_index, error_index, error] => Unspecified {
+            dispatch_error_index,
+            error_index,
+            error,
+        },
+        _ => return None,
+    })
+}
+
+// Strictly decodes a status code like `try_decode_from_u32` through
+// `fast_decode`. Only invalid codes go through the generic decoder, for its
+// error.
+pub fn fast_decode_from_u32(value: u32) -> Result<PopApiError, DecodeError> {
+    match fast_decode(value) {
+        Some(error) => Ok(error),
+        None => try_decode_from_u32(value),
+    }
+}
+
+// The use case error of a status code, without decoding the full error. `None`
+// if the code is not a valid use case error.
+pub fn use_case_from_u32(value: u32) -> Option<UseCaseError> {
+    let bytes = value.to_le_bytes();
+    if bytes[0] != codes::USE_CASE {
+        return None;
+    }
+    let mut input = &bytes[1..];
+    let error = UseCaseError::decode(&mut input).ok()?;
+    input.iter().all(|byte| *byte == 0).then_some(error)
+}
+
+// Decodes a status code expected to be a fungibles error, e.g. in a contract
+// that only ever calls the fungibles API.
+pub fn expect_fungibles(value: u32) -> Result<FungiblesError, ExpectError> {
+    match try_decode_from_u32(value).map_err(ExpectError::Invalid)? {
+        PopApiError::UseCase(UseCaseError::Fungibles(error)) => Ok(error),
+        error => Err(ExpectError::Unexpected(error)),
+    }
+}
+
+// The status code isn't the expected kind of error.
+#[derive(Debug, PartialEq)]
+pub enum ExpectError {
+    /// The status code doesn't decode.
+    Invalid(DecodeError),
+    /// The status code decodes to a different error than expected.
+    Unexpected(PopApiError),
+}
+
+impl fmt::Display for ExpectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectError::Invalid(error) => write!(f, "invalid status code: {error}"),
+            ExpectError::Unexpected(error) => write!(f, "unexpected error: {error}"),
+        }
+    }
+}
+
+impl PopApiError {
+    // Key for storing the error in relational databases: the status code
+    // widened to a signed integer, as SQL has no unsigned integer types.
+    pub fn db_key(&self) -> i64 {
+        u32::from(*self).into()
+    }
+
+    // The error stored under the key, keys outside of the `u32` range and keys
+    // of invalid status codes are rejected.
+    pub fn from_db_key(key: i64) -> Result<Self, parity_scale_codec::Error> {
+        let code = u32::try_from(key).map_err(|_| "Database key is out of range")?;
+        Ok(try_decode_from_u32(code)?)
+    }
+}
+
+impl PopApiError {
+    // Packs the error together with the gas used into a single word: the status
+    // code of the error in the low 32 bits, the gas in the high 32 bits.
+    pub fn pack_with_gas(&self, gas: u32) -> Result<u64, EncodeError> {
+        Ok(u64::from(gas) << 32 | u64::from(ErrorCode::to_u32(self)?))
+    }
+
+    // Unpacks a word created by `pack_with_gas` into the error and the gas used.
+    pub fn unpack_with_gas(value: u64) -> Result<(Self, u32), parity_scale_codec::Error> {
+        let error = try_decode_from_u32(value as u32)?;
+        Ok((error, (value >> 32) as u32))
+    }
+
+    // Whether retrying the call later can succeed, i.e. the error is caused by
+    // a temporary lack of resources.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, PopApiError::Exhausted | PopApiError::Unavailable)
+    }
+
+    // Encodes the status code followed by a hint of the seconds after which to
+    // retry, as a little endian `u16`. The hint is only meaningful for
+    // retryable errors, `None` is returned for any other error.
+    pub fn encode_with_retry_after(&self, seconds: u16) -> Option<Vec<u8>> {
+        if !self.is_retryable() {
+            return None;
+        }
+        let mut encoded = self.encode_to_array().to_vec();
+        encoded.extend(seconds.to_le_bytes());
+        Some(encoded)
+    }
+
+    // Decodes an error and retry after hint created by
+    // `encode_with_retry_after`.
+    pub fn decode_with_retry_after(
+        encoded: &[u8],
+    ) -> Result<(Self, u16), parity_scale_codec::Error> {
+        let [a, b, c, d, low, high] = *encoded else {
+            return Err("Retry after hint has an invalid length".into());
+        };
+        let error = try_decode_from_u32(u32::from_le_bytes([a, b, c, d]))?;
+        if !error.is_retryable() {
+            return Err("Retry after hint for an error that is not retryable".into());
+        }
+        Ok((error, u16::from_le_bytes([low, high])))
+    }
+}
+
+// The encoding of an error exceeds the four bytes of a status code.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct EncodeError {
+    // Length of the encoded error.
+    pub len: usize,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "encoded error of {} bytes doesn't fit a status code",
+            self.len
+        )
+    }
+}
+
+// Uniform access to the status code of the different error enums, e.g. for
+// generic reporting code handling `Box<dyn ErrorCode>`. The trait is object
+// safe on purpose.
+pub trait ErrorCode {
+    // The encoded error as a status code.
+    fn to_u32(&self) -> Result<u32, EncodeError>;
+    // The discriminant of the error, i.e. the first byte of its encoding.
+    fn code(&self) -> u8;
+}
+
+// Thin generic wrapper, the checks live in the non-generic `output_to_u32` so
+// that they aren't instantiated for every error type.
+fn encode_to_u32<T: Encode>(error: &T) -> Result<u32, EncodeError> {
+    output_to_u32(&encode_to_output(error))
+}
+
+fn output_to_u32(output: &FixedOutput<4>) -> Result<u32, EncodeError> {
+    if output.len > output.bytes.len() {
+        return Err(EncodeError { len: output.len });
+    }
+    Ok(u32::from_le_bytes(output.bytes))
+}
+
+fn discriminant<T: Encode>(error: &T) -> u8 {
+    encode_to_output::<4, T>(error).bytes[0]
+}
+
+impl ErrorCode for PopApiError {
+    fn to_u32(&self) -> Result<u32, EncodeError> {
+        encode_to_u32(self)
+    }
+
+    fn code(&self) -> u8 {
+        discriminant(self)
+    }
+}
+
+impl ErrorCode for UseCaseError {
+    fn to_u32(&self) -> Result<u32, EncodeError> {
+        encode_to_u32(self)
+    }
+
+    fn code(&self) -> u8 {
+        discriminant(self)
+    }
+}
+
+impl ErrorCode for FungiblesError {
+    fn to_u32(&self) -> Result<u32, EncodeError> {
+        encode_to_u32(self)
+    }
+
+    fn code(&self) -> u8 {
+        discriminant(self)
+    }
+}
+
+// The highest top-level variant index currently in use, i.e. the index of
+// `Unspecified`. Indices above it are unknown to this version of the crate.
+pub const fn max_known_variant() -> u8 {
+    15
+}
+
+// Whether the code is reserved for a future top-level variant, i.e. its
+// discriminant is above `max_known_variant()`. Codes with a known discriminant
+// that don't decode are structurally invalid instead, and not reserved. The
+// discriminant 255 of `SUCCESS_CODE` is never assigned to a variant, so its
+// codes aren't reserved either.
+pub const fn is_reserved_code(value: u32) -> bool {
+    let discriminant = value.to_le_bytes()[0];
+    discriminant > max_known_variant() && discriminant != SUCCESS_CODE.to_le_bytes()[0]
+}
+
+// All status codes of the unit and nested variants, plus a sampling of the
+// `Other`, `Module` and `Unspecified` codes. Meant as fixture for downstream
+// crates testing their own decoding logic.
+pub fn all_valid_codes() -> Vec<u32> {
+    use PopApiError::*;
+    let mut errors: Vec<_> = PopApiError::all_variants()
+        .into_iter()
+        .filter(|error| {
+            !matches!(
+                error,
+                Other(_) | Module(_) | UseCase(UseCaseError::Module(_)) | Unspecified { .. }
+            )
+        })
+        .collect();
+    const SAMPLES: [u8; 4] = [0, 1, 0x7f, u8::MAX];
+    for a in SAMPLES {
+        errors.push(Other(a));
+        for b in SAMPLES {
+            errors.push(Module(ModuleError { index: a, error: b }));
+            errors.push(UseCase(UseCaseError::Module(ModuleError {
+                index: a,
+                error: b,
+            })));
+            for c in SAMPLES {
+                errors.push(Unspecified {
+                    dispatch_error_index: a,
+                    error_index: b,
+                    error: c,
+                });
+            }
+        }
+    }
+    errors.into_iter().map(u32::from).collect()
+}
+
+impl PopApiError {
+    // Every variant of the error in codec order, nested variants included.
+    // Variants carrying a payload are represented once with fixed bytes.
+    pub fn all_variants() -> Vec<Self> {
+        use PopApiError::*;
+        let mut errors = vec![
+            Other(1),
+            CannotLookup,
+            BadOrigin,
+            Module(ModuleError { index: 1, error: 2 }),
+            ConsumerRemaining,
+            NoProviders,
+            TooManyConsumers,
+        ];
+        errors.extend(TokenError::all().iter().copied().map(Token));
+        errors.extend(ArithmeticError::all().iter().copied().map(Arithmetic));
+        errors.extend(TransactionalError::all().iter().copied().map(Transactional));
+        errors.extend([Exhausted, Corruption, Unavailable, RootNotAllowed]);
+        errors.extend(
+            FungiblesError::all()
+                .iter()
+                .map(|error| UseCase(UseCaseError::Fungibles(*error))),
+        );
+        errors.push(UseCase(UseCaseError::Module(ModuleError {
+            index: 1,
+            error: 2,
+        })));
+        errors.push(Unspecified {
+            dispatch_error_index: 3,
+            error_index: 2,
+            error: 1,
+        });
+        errors
+    }
+}
+
+impl TokenError {
+    pub const fn all() -> &'static [Self] {
+        use TokenError::*;
+        &[
+            FundsUnavailable,
+            OnlyProvider,
+            BelowMinimum,
+            CannotCreate,
+            UnknownAsset,
+            Frozen,
+            Unsupported,
+            CannotCreateHold,
+            NotExpendable,
+            Blocked,
+        ]
+    }
+}
+
+impl ArithmeticError {
+    pub const fn all() -> &'static [Self] {
+        use ArithmeticError::*;
+        &[Underflow, Overflow, DivisionByZero]
+    }
+}
+
+impl TransactionalError {
+    pub const fn all() -> &'static [Self] {
+        use TransactionalError::*;
+        &[LimitReached, NoLayer]
+    }
+}
+
+impl PopApiError {
+    // Rank of the error in terms of how much it tells the developer, higher is
+    // more specific:
+    // 4. `UseCase`, the error has been refined by the runtime's conversion logic.
+    // 3. Typed SDK errors, e.g. `BadOrigin`, `Token(_)` or `Arithmetic(_)`.
+    // 2. `Module`, the pallet and error index are known but not their meaning.
+    // 1. `Unspecified`, only the position within the `DispatchError` is known.
+    // 0. `Other`, an opaque error.
+    fn specificity(&self) -> u8 {
+        use PopApiError::*;
+        match self {
+            UseCase(_) => 4,
+            CannotLookup | BadOrigin | ConsumerRemaining | NoProviders | TooManyConsumers
+            | Token(_) | Arithmetic(_) | Transactional(_) | Exhausted | Corruption
+            | Unavailable | RootNotAllowed => 3,
+            Module(_) => 2,
+            Unspecified { .. } => 1,
+            Other(_) => 0,
+        }
+    }
+}
+
+// Picks the most specific error out of several candidates describing the same
+// failure, e.g. a generic `Module` error and its refined `UseCase` mapping. The
+// ranking is: use case > typed SDK error > module > unspecified > other. When
+// multiple candidates share the highest rank the first one is returned. An
+// empty slice results in `Other(0)` as nothing is known about the failure.
+pub fn most_specific(candidates: &[PopApiError]) -> PopApiError {
+    candidates
+        .iter()
+        .copied()
+        .reduce(|best, candidate| {
+            if candidate.specificity() > best.specificity() {
+                candidate
+            } else {
+                best
+            }
+        })
+        .unwrap_or(PopApiError::Other(0))
+}
+
+// The `Display` implementations write straight into the `Formatter` so that no
+// allocation is required, which keeps them usable in `no_std` environments
+// without `alloc`.
+impl fmt::Display for PopApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PopApiError::*;
+        match self {
+            Other(index) => write!(f, "other error: {index}"),
+            CannotLookup => f.write_str("failed to lookup some data"),
+            BadOrigin => f.write_str("bad origin"),
+            Module(ModuleError { index, error }) => {
+                write!(f, "module error: pallet index {index}, error index {error}")
+            }
+            ConsumerRemaining => {
+                f.write_str("at least one consumer is remaining so the account cannot be destroyed")
+            }
+            NoProviders => f.write_str("there are no providers so the account cannot be created"),
+            TooManyConsumers => f.write_str("account has too many consumers"),
+            Token(error) => write!(f, "token error: {error}"),
+            Arithmetic(error) => write!(f, "arithmetic error: {error}"),
+            Transactional(error) => write!(f, "transactional error: {error}"),
+            Exhausted => f.write_str("resources exhausted"),
+            Corruption => f.write_str("state is corrupt"),
+            Unavailable => f.write_str("resource is not available"),
+            RootNotAllowed => f.write_str("root origin is not allowed"),
+            UseCase(error) => write!(f, "{error}"),
+            Unspecified {
+                dispatch_error_index,
+                error_index,
+                error,
+            } => write!(
+                f,
+                "unspecified error: dispatch error index {dispatch_error_index}, error index {error_index}, error {error}"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for UseCaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UseCaseError::Fungibles(error) => write!(f, "fungibles error: {error}"),
+            UseCaseError::Module(ModuleError { index, error }) => write!(
+                f,
+                "module error within a use case: pallet index {index}, error index {error}"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TokenError::*;
+        f.write_str(match self {
+            FundsUnavailable => "funds are unavailable",
+            OnlyProvider => "account that must exist would die",
+            BelowMinimum => "account cannot exist with the funds that would be given",
+            CannotCreate => "account cannot be created",
+            UnknownAsset => "the asset in question is unknown",
+            Frozen => "funds exist but are frozen",
+            Unsupported => "operation is not supported by the asset",
+            CannotCreateHold => "account cannot be created for recording amount on hold",
+            NotExpendable => "account that is desired to remain would die",
+            Blocked => "account cannot receive the assets",
+        })
+    }
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ArithmeticError::*;
+        f.write_str(match self {
+            Underflow => "underflow",
+            Overflow => "overflow",
+            DivisionByZero => "division by zero",
+        })
+    }
+}
+
+impl fmt::Display for TransactionalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TransactionalError::*;
+        f.write_str(match self {
+            LimitReached => "too many transactional layers have been spawned",
+            NoLayer => "a transactional layer was expected, but does not exist",
+        })
+    }
+}
+
+impl PopApiError {
+    // A longer explanation of the error than its `Display` message, meant for
+    // developers looking into a failure.
+    pub fn details(&self) -> &'static str {
+        use PopApiError::*;
+        match self {
+            Other(_) => "An error that is not further specified by the runtime. The index identifies the error among the messages known to the runtime, `0` if the message is unknown.",
+            CannotLookup => "A lookup failed, e.g. an account index or address could not be resolved.",
+            BadOrigin => "The origin of the call is not allowed to perform the operation.",
+            Module(_) => "An error of a pallet that the runtime did not convert into a use case error. The pallet index and error index identify the error within the runtime metadata.",
+            ConsumerRemaining => "The account still has consumer references, e.g. holds or locks, and therefore cannot be removed.",
+            NoProviders => "The account has no provider references, it needs to receive funds before it can exist.",
+            TooManyConsumers => "The account has reached the maximum number of consumer references and cannot take on another one.",
+            Token(_) => "An error related to the transfer or holding of tokens, see the token error for the reason.",
+            Arithmetic(_) => "An arithmetic operation failed, see the arithmetic error for the reason.",
+            Transactional(_) => "The limit of nested transactional layers has been reached or a transactional layer was missing.",
+            Exhausted => "A resource has been exhausted, e.g. the data to read or write is too large.",
+            Corruption => "The chain state is corrupt. This is not expected to resolve itself.",
+            Unavailable => "A resource is unavailable right now. This might resolve itself later.",
+            RootNotAllowed => "The root origin is not allowed to perform the operation.",
+            UseCase(UseCaseError::Fungibles(error)) => error.details(),
+            UseCase(UseCaseError::Module(_)) => "An error of a pallet called within a use case that has no use case error of its own. The pallet index and error index identify the error within the runtime metadata.",
+            Unspecified { .. } => "An error that was unknown when the contract was deployed. The indices identify the error within the `DispatchError` of the runtime.",
+        }
+    }
+
+    // Formats the full explanation of the error into any `fmt::Write` sink
+    // without allocating: its path, encoded bytes including the payload,
+    // message and details. A sink that runs out of space returns `fmt::Error`.
+    pub fn write_explanation(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        write!(
+            w,
+            "{} {:?}: {self}. {}",
+            self.path(),
+            self.encode_to_array(),
+            self.details()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_encoding_stable;
+
+    register_error_tests!(FungiblesError, UseCaseError::Fungibles);
+    register_error_tests!(TokenError, PopApiError::Token);
+    register_error_tests!(ArithmeticError, PopApiError::Arithmetic);
+    register_error_tests!(TransactionalError, PopApiError::Transactional);
+
+    // Fails to compile when a use case is added without an arm here. The arm of
+    // a use case wrapping an error enum names the `REGISTERED` constant, which
+    // only exists once `register_error_tests!` is invoked for the enum. A use
+    // case with a payload instead, like `Module`, needs its own tests, this
+    // test can't check that they exist.
+    #[test]
+    fn test_use_cases_are_registered() {
+        for error in PopApiError::all_variants() {
+            if let PopApiError::UseCase(use_case) = error {
+                match use_case {
+                    UseCaseError::Fungibles(_) => Fungibles::REGISTERED,
+                    // Carries a payload, see `test_use_case_module_round_trip`.
+                    UseCaseError::Module(_) => (),
+                }
+            }
+        }
+    }
+
+    // A module error within a use case is distinct from the top-level one.
+    #[test]
+    fn test_use_case_module_round_trip() {
+        let module = ModuleError {
+            index: 52,
+            error: 3,
+        };
+        let error = PopApiError::UseCase(UseCaseError::Module(module));
+        assert_encoding_stable!(error, [14, 1, 52, 3]);
+        let code = u32::from(error);
+        assert_eq!(try_decode_from_u32(code), Ok(error));
+        assert_eq!(fast_decode(code), Some(error));
+        assert_eq!(const_status_code(error), code);
+        assert_ne!(code, u32::from(PopApiError::Module(module)));
+        assert_eq!(error.path(), "UseCase::Module");
+        assert_eq!(
+            error.to_string(),
+            "module error within a use case: pallet index 52, error index 3"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_module_error_encoding_decoding() {
+        let error = PopApiError::Module(ModuleError { index: 1, error: 2 });
+        println!("Error: {error:?}");
+        let value_u32 = encode_and_decode_to_u32(error);
+        println!("U32: {value_u32}");
+        let decoded_error = encode_and_decode_to_pop_api_error(value_u32);
+        assert_eq!(error, decoded_error);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_use_case_error_encoding_decoding() {
+        let error =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        println!("Error: {error:?}");
+        let value_u32 = encode_and_decode_to_u32(error);
+        println!("U32: {value_u32}");
+        let decoded_error = encode_and_decode_to_pop_api_error(value_u32);
+        assert_eq!(error, decoded_error);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_unspecified_error_encoding_decoding() {
+        let error = PopApiError::Unspecified {
+            dispatch_error_index: 3,
+            error_index: 2,
+            error: 1,
+        };
+        println!("Error: {error:?}");
+        let value_u32 = encode_and_decode_to_u32(error);
+        println!("U32: {value_u32}");
+        let decoded_error = encode_and_decode_to_pop_api_error(value_u32);
+        assert_eq!(error, decoded_error);
+    }
+
+    #[test]
+    fn test_most_specific_prefers_use_case() {
+        let use_case =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        let candidates = [
+            PopApiError::Other(1),
+            PopApiError::Module(ModuleError {
+                index: 52,
+                error: 3,
+            }),
+            use_case,
+            PopApiError::BadOrigin,
+            PopApiError::Unspecified {
+                dispatch_error_index: 3,
+                error_index: 2,
+                error: 1,
+            },
+        ];
+        assert_eq!(most_specific(&candidates), use_case);
+    }
+
+    #[test]
+    fn test_most_specific_ranking() {
+        let module = PopApiError::Module(ModuleError {
+            index: 52,
+            error: 3,
+        });
+        let unspecified = PopApiError::Unspecified {
+            dispatch_error_index: 3,
+            error_index: 2,
+            error: 1,
+        };
+        let arithmetic = PopApiError::Arithmetic(ArithmeticError::Overflow);
+        assert_eq!(most_specific(&[module, arithmetic]), arithmetic);
+        assert_eq!(most_specific(&[unspecified, module]), module);
+        assert_eq!(
+            most_specific(&[PopApiError::Other(0), unspecified]),
+            unspecified
+        );
+        assert_eq!(
+            most_specific(&[PopApiError::Other(2)]),
+            PopApiError::Other(2)
+        );
+    }
+
+    #[test]
+    fn test_most_specific_ties_and_empty() {
+        // First candidate wins on equal specificity.
+        assert_eq!(
+            most_specific(&[PopApiError::BadOrigin, PopApiError::Exhausted]),
+            PopApiError::BadOrigin
+        );
+        assert_eq!(most_specific(&[]), PopApiError::Other(0));
+    }
+
+    // Fixed-size `core::fmt::Write` sink, formatting into it proves that the
+    // `Display` implementations don't need to allocate.
+    struct FixedBuffer<const N: usize> {
+        buf: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> FixedBuffer<N> {
+        fn new() -> Self {
+            Self {
+                buf: [0; N],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buf[..self.len]).unwrap()
+        }
+    }
+
+    impl<const N: usize> fmt::Write for FixedBuffer<N> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > N {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_display_without_allocation() {
+        use core::fmt::Write;
+
+        let cases = [
+            (PopApiError::BadOrigin, "bad origin"),
+            (
+                PopApiError::Module(ModuleError { index: 1, error: 2 }),
+                "module error: pallet index 1, error index 2",
+            ),
+            (
+                PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+                "fungibles error: not enough balance to fulfill the request",
+            ),
+            (
+                PopApiError::Unspecified {
+                    dispatch_error_index: 3,
+                    error_index: 2,
+                    error: 1,
+                },
+                "unspecified error: dispatch error index 3, error index 2, error 1",
+            ),
+        ];
+        for (error, expected) in cases {
+            let mut buffer = FixedBuffer::<128>::new();
+            write!(buffer, "{error}").unwrap();
+            assert_eq!(buffer.as_str(), expected);
+        }
+        // A buffer that is too small is reported through `fmt::Error`.
+        let mut buffer = FixedBuffer::<4>::new();
+        assert!(write!(buffer, "{}", PopApiError::BadOrigin).is_err());
+    }
+
+    #[test]
+    fn test_write_explanation() {
+        let error = PopApiError::Module(ModuleError { index: 1, error: 2 });
+        let mut buffer = FixedBuffer::<512>::new();
+        error.write_explanation(&mut buffer).unwrap();
+        assert_eq!(
+            buffer.as_str(),
+            format!(
+                "Module [3, 1, 2, 0]: module error: pallet index 1, error index 2. {}",
+                error.details()
+            )
+        );
+        // Running out of space is an error of the sink, not a panic.
+        let mut buffer = FixedBuffer::<16>::new();
+        assert!(error.write_explanation(&mut buffer).is_err());
+        for error in PopApiError::all_variants() {
+            let mut buffer = FixedBuffer::<512>::new();
+            error.write_explanation(&mut buffer).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_all_valid_codes_decode() {
+        let codes = all_valid_codes();
+        // 9 unit variants, 25 nested variants and 100 payload samples.
+        assert_eq!(codes.len(), 134);
+        for code in codes {
+            let error = try_decode_from_u32(code)
+                .unwrap_or_else(|e| panic!("code {code} failed to decode: {e:?}"));
+            assert_eq!(u32::from(error), code);
+        }
+    }
+
+    // The ABI depends on the encoding being a bijection between errors and
+    // valid codes: `decode(encode(error)) == error` for every constructible
+    // error and `encode(decode(code)) == code` for every valid code.
+    #[test]
+    fn test_encoding_is_a_bijection() {
+        use PopApiError::*;
+        let mut errors: Vec<_> = PopApiError::all_variants()
+            .into_iter()
+            .filter(|error| {
+                !matches!(
+                    error,
+                    Other(_) | Module(_) | UseCase(UseCaseError::Module(_)) | Unspecified { .. }
+                )
+            })
+            .collect();
+        for a in 0..=u8::MAX {
+            errors.push(Other(a));
+            for b in 0..=u8::MAX {
+                errors.push(Module(ModuleError { index: a, error: b }));
+                errors.push(UseCase(UseCaseError::Module(ModuleError {
+                    index: a,
+                    error: b,
+                })));
+                errors.push(Unspecified {
+                    dispatch_error_index: a,
+                    error_index: b,
+                    error: a ^ b,
+                });
+            }
+        }
+        for error in errors {
+            let code = u32::from(error);
+            assert_eq!(
+                try_decode_from_u32(code),
+                Ok(error),
+                "{error:?} encodes to {code}, which doesn't decode back"
+            );
+        }
+        for code in all_valid_codes() {
+            let error = try_decode_from_u32(code)
+                .unwrap_or_else(|e| panic!("code {code} failed to decode: {e:?}"));
+            assert_eq!(
+                u32::from(error),
+                code,
+                "code {code} decodes to {error:?}, which doesn't encode back"
+            );
+        }
+    }
+
+    // The stack based encoding and decoding must match the original `Vec` based
+    // helpers for every variant.
+    #[test]
+    #[allow(deprecated)]
+    fn test_array_encoding_matches_vec_encoding() {
+        for error in PopApiError::all_variants() {
+            let mut encoded = error.encode();
+            encoded.resize(4, 0);
+            assert_eq!(error.encode_to_array()[..], encoded[..], "{error:?}");
+            let code = encode_and_decode_to_u32(error);
+            assert_eq!(u32::from(error), code, "{error:?}");
+            let legacy = PopApiError::decode(&mut &code.encode()[..]).unwrap();
+            assert_eq!(encode_and_decode_to_pop_api_error(code), legacy);
+            assert_eq!(try_decode_from_u32(code), Ok(legacy));
+        }
+    }
+
+    #[test]
+    fn test_try_decode_rejects_non_zero_padding() {
+        let code = u32::from(PopApiError::BadOrigin);
+        assert_eq!(PopApiError::try_from(code), Ok(PopApiError::BadOrigin));
+        assert!(try_decode_from_u32(code | 1 << 8).is_err());
+    }
+
+    #[test]
+    fn test_decode_error_position() {
+        let error = |bytes| try_decode_from_u32(u32::from_le_bytes(bytes)).unwrap_err();
+        // Unknown top-level variant.
+        assert_eq!(error([16, 0, 0, 0]).position, 0);
+        // Unknown fungibles error.
+        let inner = error([14, 0, 10, 0]);
+        assert_eq!(inner.position, 2);
+        assert!(inner.to_string().ends_with("(position: 2)"));
+        // Unknown use case and non-zero padding.
+        assert_eq!(error([14, 2, 0, 0]).position, 1);
+        assert_eq!(error([2, 0, 0, 1]).position, 3);
+    }
+
+    #[test]
+    fn test_all_variants_are_distinct() {
+        let variants = PopApiError::all_variants();
+        for (i, error) in variants.iter().enumerate() {
+            assert!(!variants[i + 1..].contains(error), "duplicate {error:?}");
+        }
+    }
+
+    // Renders the `Display` and `details()` text of every variant. The output is
+    // compared against the reviewed snapshot, run with `UPDATE_SNAPSHOTS=1` to
+    // update the snapshot after changing a message on purpose.
+    #[test]
+    fn test_messages_snapshot() {
+        const SNAPSHOT: &str = "src/snapshots/messages.snap";
+        let rendered: String = PopApiError::all_variants()
+            .into_iter()
+            .map(|error| {
+                format!(
+                    "{error:?}\n  display: {error}\n  details: {}\n",
+                    error.details()
+                )
+            })
+            .collect();
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(SNAPSHOT, &rendered).unwrap();
+            return;
+        }
+        assert_eq!(
+            rendered,
+            include_str!("snapshots/messages.snap"),
+            "messages changed, run with `UPDATE_SNAPSHOTS=1` and review {SNAPSHOT}"
+        );
+    }
+
+    #[test]
+    fn test_error_code_trait_objects() {
+        let errors: Vec<Box<dyn ErrorCode>> = vec![
+            Box::new(PopApiError::BadOrigin),
+            Box::new(PopApiError::UseCase(UseCaseError::Fungibles(
+                FungiblesError::InsufficientBalance,
+            ))),
+            Box::new(UseCaseError::Fungibles(FungiblesError::NoAccount)),
+            Box::new(FungiblesError::Unknown),
+        ];
+        let codes: Vec<_> = errors
+            .iter()
+            .map(|error| (error.code(), error.to_u32().unwrap()))
+            .collect();
+        assert_eq!(codes, [(2, 2), (14, 14 | 3 << 16), (0, 6 << 8), (8, 8)]);
+    }
+
+    #[test]
+    fn test_try_decode_rejects_invalid_nested_discriminants() {
+        let error = |bytes| {
+            try_decode_from_u32(u32::from_le_bytes(bytes))
+                .unwrap_err()
+                .to_string()
+        };
+        // Unknown use case.
+        assert!(
+            error([14, 2, 0, 0]).contains("Could not decode `UseCaseError`, variant doesn't exist")
+        );
+        // Unknown fungibles error.
+        assert!(error([14, 0, 10, 0])
+            .contains("Could not decode `FungiblesError`, variant doesn't exist"));
+        // Valid nesting followed by garbage.
+        assert_eq!(
+            error([14, 0, 3, 7]),
+            "Status code has non-zero padding (position: 3)"
+        );
+    }
+
+    #[test]
+    fn test_db_key_round_trip() {
+        for error in PopApiError::all_variants() {
+            let key = error.db_key();
+            assert_eq!(key, i64::from(u32::from(error)));
+            assert_eq!(PopApiError::from_db_key(key), Ok(error));
+        }
+    }
+
+    #[test]
+    fn test_from_db_key_rejects_invalid_keys() {
+        assert!(PopApiError::from_db_key(-1).is_err());
+        assert!(PopApiError::from_db_key(i64::from(u32::MAX) + 1).is_err());
+        // Within range but not a valid status code.
+        assert!(PopApiError::from_db_key(i64::from(u32::MAX)).is_err());
+    }
+
+    // Decodes the code, a successful decode has to re-encode to the same code.
+    // Returns whether the code is valid.
+    fn check_status_code(code: u32) -> bool {
+        // The original decoding through an encoded `Vec`.
+        let encoded = code.encode();
+        let mut input = &encoded[..];
+        let legacy = PopApiError::decode(&mut input)
+            .ok()
+            .filter(|_| input.iter().all(|byte| *byte == 0));
+        assert_eq!(
+            PopApiError::from_le_bytes(code.to_le_bytes()).ok(),
+            legacy,
+            "{code} decodes differently than before"
+        );
+        match try_decode_from_u32(code) {
+            Ok(error) => {
+                assert_eq!(
+                    u32::from(error),
+                    code,
+                    "{error:?} doesn't re-encode to {code}"
+                );
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    #[test]
+    fn sweep_status_codes_sampled() {
+        // Every combination of the first two bytes, plus a sample over the whole
+        // space.
+        (0..=u16::MAX.into()).for_each(|code| {
+            check_status_code(code);
+        });
+        (0..=u32::MAX).step_by(65_521).for_each(|code| {
+            check_status_code(code);
+        });
+    }
+
+    // Takes a while, run in release mode:
+    // `cargo test --release sweep_status_codes -- --ignored`.
+    #[test]
+    #[ignore]
+    fn sweep_status_codes() {
+        let valid = (0..=u32::MAX)
+            .filter(|code| check_status_code(*code))
+            .count();
+        // Every variant without payload has a single code, `Other`, both
+        // `Module` errors and `Unspecified` have a code for every value of their
+        // payload bytes.
+        let without_payload = PopApiError::all_variants()
+            .iter()
+            .filter(|error| {
+                !matches!(
+                    error,
+                    PopApiError::Other(_)
+                        | PopApiError::Module(_)
+                        | PopApiError::UseCase(UseCaseError::Module(_))
+                        | PopApiError::Unspecified { .. }
+                )
+            })
+            .count();
+        assert_eq!(
+            valid,
+            without_payload + (1 << 8) + (1 << 16) + (1 << 16) + (1 << 24)
+        );
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_public_types_are_send_and_sync() {
+        assert_send_sync::<PopApiError>();
+        assert_send_sync::<UseCaseError>();
+        assert_send_sync::<FungiblesError>();
+        assert_send_sync::<ModuleError>();
+        assert_send_sync::<EncodeError>();
+        assert_send_sync::<UserAction>();
+    }
+
+    #[test]
+    fn test_concurrent_decoding() {
+        let codes = std::sync::Arc::new(all_valid_codes());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let codes = codes.clone();
+                std::thread::spawn(move || {
+                    codes
+                        .iter()
+                        .map(|code| try_decode_from_u32(*code).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let expected: Vec<_> = codes
+            .iter()
+            .map(|code| try_decode_from_u32(*code).unwrap())
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh_round_trip() {
+        for error in [
+            PopApiError::BadOrigin,
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+        ] {
+            let bytes = borsh::to_vec(&error).unwrap();
+            assert_eq!(borsh::from_slice::<PopApiError>(&bytes).unwrap(), error);
+        }
+    }
+
+    // Any error: a variant of `all_variants` with its payload replaced by
+    // arbitrary bytes.
+    #[cfg(feature = "serde")]
+    fn arbitrary_error() -> impl proptest::strategy::Strategy<Value = PopApiError> {
+        use proptest::prelude::*;
+        use PopApiError::*;
+        let variants = PopApiError::all_variants();
+        (0..variants.len(), any::<[u8; 3]>()).prop_map(move |(i, [a, b, c])| match variants[i] {
+            Other(_) => Other(a),
+            Module(_) => Module(ModuleError { index: a, error: b }),
+            UseCase(UseCaseError::Module(_)) => {
+                UseCase(UseCaseError::Module(ModuleError { index: a, error: b }))
+            }
+            Unspecified { .. } => Unspecified {
+                dispatch_error_index: a,
+                error_index: b,
+                error: c,
+            },
+            error => error,
+        })
+    }
+
+    // The JSON representation has to round trip and agree with the status
+    // code, catching serde tagging mistakes.
+    #[cfg(feature = "serde")]
+    proptest::proptest! {
+        #[test]
+        fn serde_json_round_trip(error in arbitrary_error()) {
+            let json = serde_json::to_string(&error).unwrap();
+            let decoded: PopApiError = serde_json::from_str(&json).unwrap();
+            proptest::prop_assert_eq!(decoded, error);
+            proptest::prop_assert_eq!(u32::from(decoded), u32::from(error));
+        }
+    }
+
+    // Zero is the first variant with a zero payload, not a success code.
+    #[test]
+    fn test_zero_is_other() {
+        assert_eq!(try_decode_from_u32(0), Ok(PopApiError::Other(0)));
+        assert_eq!(fast_decode(0), Some(PopApiError::Other(0)));
+        assert_eq!(u32::from(PopApiError::Other(0)), 0);
+        assert!(all_valid_codes().contains(&0));
+    }
+
+    #[test]
+    fn test_decode_status() {
+        assert_eq!(decode_status(SUCCESS_CODE), Ok(None));
+        assert!(!is_reserved_code(SUCCESS_CODE));
+        assert!(!all_valid_codes().contains(&SUCCESS_CODE));
+        assert_eq!(decode_status(0), Ok(Some(PopApiError::Other(0))));
+        let error = PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::NoAccount));
+        assert_eq!(decode_status(error.into()), Ok(Some(error)));
+        assert!(decode_status(u32::from_le_bytes([14, 0, 10, 0])).is_err());
+        assert!(decode_status(u32::from_le_bytes([254, 255, 255, 255])).is_err());
+    }
+
+    #[test]
+    fn test_max_known_variant() {
+        let max = PopApiError::all_variants()
+            .iter()
+            .map(|error| error.code())
+            .max();
+        assert_eq!(max, Some(max_known_variant()));
+    }
+
+    // `#[derive(ToStatusCode)]` rejects offsets below this number of variants,
+    // which `encoding-macros` can't take from this crate.
+    #[test]
+    fn test_pop_api_error_variants() {
+        assert_eq!(max_known_variant() + 1, 16);
+    }
+
+    #[test]
+    fn test_is_reserved_code() {
+        // A future top-level variant, possibly with a payload.
+        assert!(is_reserved_code(16));
+        assert!(is_reserved_code(u32::from_le_bytes([254, 1, 2, 3])));
+        // The discriminant of the success code.
+        assert!(!is_reserved_code(u32::from_le_bytes([u8::MAX, 1, 2, 3])));
+        // Garbage with a known discriminant: an invalid nested error and non-zero
+        // padding.
+        assert!(!is_reserved_code(u32::from_le_bytes([14, 0, 10, 0])));
+        assert!(!is_reserved_code(u32::from_le_bytes([2, 0, 0, 1])));
+        assert!(all_valid_codes()
+            .into_iter()
+            .all(|code| !is_reserved_code(code)));
+    }
+
+    #[test]
+    fn test_unspecified_error_byte_layout() {
+        let error = PopApiError::Unspecified {
+            dispatch_error_index: 3,
+            error_index: 2,
+            error: 1,
+        };
+        // Discriminant followed by the fields in declaration order.
+        assert_encoding_stable!(error, [15, 3, 2, 1]);
+        assert_eq!(error.encode_to_array(), [15, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_pack_with_gas_round_trip() {
+        let error =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        let packed = error.pack_with_gas(123_456).unwrap();
+        assert_eq!(packed, 123_456 << 32 | u64::from(u32::from(error)));
+        assert_eq!(PopApiError::unpack_with_gas(packed), Ok((error, 123_456)));
+        // Invalid status code in the low bits.
+        assert!(PopApiError::unpack_with_gas(1 << 32 | u64::from(u32::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_const_status_code() {
+        const INSUFFICIENT_BALANCE: u32 = const_status_code(PopApiError::UseCase(
+            UseCaseError::Fungibles(FungiblesError::InsufficientBalance),
+        ));
+        assert_eq!(INSUFFICIENT_BALANCE, u32::from_le_bytes([14, 0, 3, 0]));
+        for error in PopApiError::all_variants() {
+            assert_eq!(const_status_code(error), u32::from(error), "{error:?}");
+        }
+        for code in all_valid_codes() {
+            assert_eq!(const_status_code(try_decode_from_u32(code).unwrap()), code);
+        }
+    }
+
+    #[test]
+    fn test_expect_fungibles() {
+        let code = u32::from(PopApiError::UseCase(UseCaseError::Fungibles(
+            FungiblesError::NoAccount,
+        )));
+        assert_eq!(expect_fungibles(code), Ok(FungiblesError::NoAccount));
+        assert_eq!(
+            expect_fungibles(u32::from(PopApiError::BadOrigin)),
+            Err(ExpectError::Unexpected(PopApiError::BadOrigin))
+        );
+        // A use case other than fungibles, which doesn't exist yet.
+        let error = expect_fungibles(u32::from_le_bytes([14, 2, 0, 0])).unwrap_err();
+        assert!(matches!(error, ExpectError::Invalid(_)));
+        assert!(error.to_string().starts_with("invalid status code: "));
+    }
+
+    #[test]
+    fn test_decode_with_remainder() {
+        let error = PopApiError::Module(ModuleError { index: 1, error: 2 });
+        let encoded = error.encode();
+        assert_eq!(decode_with_remainder(&encoded), Ok((error, &[][..])));
+        let input = [3, 1, 2, 0xaa, 0xbb, 0xcc];
+        assert_eq!(
+            decode_with_remainder(&input),
+            Ok((error, &[0xaa, 0xbb, 0xcc][..]))
+        );
+        // Any type, e.g. a `Vec` with its length prefix.
+        assert_eq!(
+            decode_with_remainder::<Vec<u8>>(&[8, 1, 2, 3]),
+            Ok((vec![1, 2], &[3][..]))
+        );
+        // Truncated after consuming two bytes, the input is unaffected.
+        let input = [3, 1];
+        assert!(decode_with_remainder::<PopApiError>(&input).is_err());
+        assert_eq!(input, [3, 1]);
+    }
+
+    #[test]
+    fn test_decode_counting() {
+        let trailing = [0xaa, 0xbb];
+        let cases = [
+            (PopApiError::BadOrigin, 1),
+            (PopApiError::Module(ModuleError { index: 1, error: 2 }), 3),
+            (
+                PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance)),
+                3,
+            ),
+        ];
+        for (error, len) in cases {
+            let mut input = error.encode();
+            input.extend(trailing);
+            assert_eq!(decode_counting(&input), Ok((error, len)));
+            assert_eq!(input[len..], trailing);
+        }
+        assert!(decode_counting(&[3, 1]).is_err());
+        assert!(decode_counting(&[]).is_err());
+    }
+
+    #[test]
+    fn test_use_case_from_u32() {
+        let error = UseCaseError::Fungibles(FungiblesError::InsufficientBalance);
+        assert_eq!(
+            use_case_from_u32(u32::from(PopApiError::UseCase(error))),
+            Some(error)
+        );
+        assert_eq!(use_case_from_u32(u32::from(PopApiError::BadOrigin)), None);
+        assert_eq!(use_case_from_u32(u32::from_le_bytes([14, 0, 10, 0])), None);
+        assert_eq!(
+            use_case_from_u32(u32::from_le_bytes([14, 1, 52, 3])),
+            Some(UseCaseError::Module(ModuleError {
+                index: 52,
+                error: 3
+            }))
+        );
+        assert_eq!(use_case_from_u32(u32::from_le_bytes([14, 2, 0, 0])), None);
+        assert_eq!(use_case_from_u32(u32::from_le_bytes([14, 0, 3, 1])), None);
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode, MaxEncodedLen)]
+    enum SmallError {
+        A,
+        B(u8),
+        C { x: u8, y: u16 },
+    }
+
+    #[test]
+    fn test_generic_status_code() {
+        assert_eq!(PopApiError::max_encoded_len(), 4);
+        for error in [
+            SmallError::A,
+            SmallError::B(7),
+            SmallError::C { x: 1, y: 0x0302 },
+        ] {
+            let code = to_status_code(&error);
+            assert_eq!(
+                code.to_le_bytes()[..error.encoded_size()],
+                error.encode()[..]
+            );
+            assert_eq!(from_status_code::<SmallError>(code), Ok(error));
+        }
+        assert!(from_status_code::<SmallError>(u32::from_le_bytes([0, 1, 0, 0])).is_err());
+        assert!(from_status_code::<SmallError>(u32::from_le_bytes([3, 0, 0, 0])).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding doesn't fit a status code")]
+    fn test_to_status_code_rejects_large_types() {
+        to_status_code(&u64::MAX);
+    }
+
+    #[test]
+    fn test_const_fast_decode() {
+        const BAD_ORIGIN: Option<PopApiError> = fast_decode(2);
+        assert_eq!(BAD_ORIGIN, Some(PopApiError::BadOrigin));
+        for code in all_valid_codes() {
+            assert_eq!(fast_decode(code), try_decode_from_u32(code).ok(), "{code}");
+        }
+        for code in (0..=u32::MAX).step_by(65_521).chain(0..=u16::MAX.into()) {
+            assert_eq!(fast_decode(code), try_decode_from_u32(code).ok(), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_fast_decode_matches_generic_decode() {
+        for code in all_valid_codes() {
+            assert_eq!(
+                fast_decode_from_u32(code),
+                try_decode_from_u32(code),
+                "{code}"
+            );
+        }
+        // Invalid codes around every discriminant.
+        for discriminant in 0..=u8::MAX {
+            for byte in [0, 1, 9, 10, u8::MAX] {
+                for code in [
+                    u32::from_le_bytes([discriminant, byte, 0, 0]),
+                    u32::from_le_bytes([discriminant, 0, byte, 0]),
+                    u32::from_le_bytes([discriminant, 0, 0, byte]),
+                ] {
+                    assert_eq!(
+                        fast_decode_from_u32(code),
+                        try_decode_from_u32(code),
+                        "{code}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_retry_after_round_trip() {
+        let encoded = PopApiError::Exhausted.encode_with_retry_after(30).unwrap();
+        assert_eq!(encoded, [10, 0, 0, 0, 30, 0]);
+        assert_eq!(
+            PopApiError::decode_with_retry_after(&encoded),
+            Ok((PopApiError::Exhausted, 30))
+        );
+        let encoded = PopApiError::Unavailable
+            .encode_with_retry_after(u16::MAX)
+            .unwrap();
+        assert_eq!(
+            PopApiError::decode_with_retry_after(&encoded),
+            Ok((PopApiError::Unavailable, u16::MAX))
+        );
+    }
+
+    #[test]
+    fn test_retry_after_rejects_non_retryable_errors() {
+        assert_eq!(PopApiError::BadOrigin.encode_with_retry_after(30), None);
+        assert!(PopApiError::decode_with_retry_after(&[2, 0, 0, 0, 30, 0]).is_err());
+        assert!(PopApiError::decode_with_retry_after(&[10, 0, 0, 0, 30]).is_err());
+        assert!(PopApiError::decode_with_retry_after(&[10, 0, 0, 1, 30, 0]).is_err());
+    }
+
+    // Tooling parses the `Debug` output, changing it breaks those consumers.
+    #[test]
+    fn test_debug_output() {
+        assert_eq!(
+            format!(
+                "{:?}",
+                PopApiError::Module(ModuleError { index: 1, error: 2 })
+            ),
+            "Module(ModuleError { index: 1, error: 2 })"
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance))
+            ),
+            "UseCase(Fungibles(InsufficientBalance))"
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                PopApiError::Unspecified {
+                    dispatch_error_index: 3,
+                    error_index: 2,
+                    error: 1,
+                }
+            ),
+            "Unspecified { dispatch_error_index: 3, error_index: 2, error: 1 }"
+        );
+    }
+
+    #[test]
+    fn encoding_possibilities() {
+        // The tour shows every kind of variant, in order, each encoding starting
+        // with the variant index.
+        let mut indices: Vec<_> = tour::scale_tour()
+            .into_iter()
+            .map(|(_, encoded)| encoded[0])
+            .collect();
+        assert!(indices.is_sorted());
+        indices.dedup();
+        assert_eq!(indices, (0..=8).collect::<Vec<u8>>());
+    }
+}
@@ -0,0 +1,140 @@
+// Defines an error enum from a single table so that adding a variant is a
+// single edit. Every entry holds the variant's codec index, its `Display`
+// message and its `details()` text, from which the enum, its `Display`
+// implementation, `details()`, `codec_index()` and `all()` are generated. The
+// attributes of the enum, e.g. its derives, are passed through as is.
+//
+// Duplicate codec indices, and with them duplicate status codes within the
+// enum, are rejected at compile time, see the `define_errors_duplicate_index`
+// UI test of `encoding-macros`, which includes this file.
+macro_rules! define_errors {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident: index $index:literal, $message:literal, $details:literal;
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                #[codec(index = $index)]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            // Every variant of the error in declaration order.
+            pub const fn all() -> &'static [Self] {
+                &[$($name::$variant),*]
+            }
+
+            // The codec index of the variant, usable in const contexts.
+            pub const fn codec_index(&self) -> u8 {
+                match self {
+                    $($name::$variant => $index,)*
+                }
+            }
+
+            // A longer explanation of the error than its `Display` message.
+            pub fn details(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $details,)*
+                }
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(match self {
+                    $($name::$variant => $message,)*
+                })
+            }
+        }
+    };
+}
+pub(crate) use define_errors;
+
+// Expands to the tests every error enum nested in `PopApiError` needs: round
+// trip, the 4 byte budget, unique status codes, `Display` and presence in the
+// catalogue. A use case wrapping an error enum also gets a `REGISTERED`
+// constant, which `test_use_cases_are_registered` names so that such a use case
+// can't be added without registering its tests.
+#[cfg(test)]
+macro_rules! register_error_tests {
+    ($error:ident, UseCaseError::$variant:ident) => {
+        register_error_tests!(@tests $error, $variant, |error| {
+            $crate::PopApiError::UseCase($crate::UseCaseError::$variant(error))
+        }, pub(super) const REGISTERED: () = (););
+    };
+    ($error:ident, PopApiError::$variant:ident) => {
+        register_error_tests!(@tests $error, $variant, $crate::PopApiError::$variant,);
+    };
+    (@tests $error:ident, $variant:ident, $wrap:expr, $($registered:item)?) => {
+        #[allow(non_snake_case)]
+        mod $variant {
+            use super::*;
+
+            $($registered)?
+
+            fn errors() -> Vec<($error, PopApiError)> {
+                $error::all()
+                    .iter()
+                    .map(|error| (*error, ($wrap)(*error)))
+                    .collect()
+            }
+
+            #[test]
+            fn test_round_trip() {
+                for (_, error) in errors() {
+                    assert_eq!(try_decode_from_u32(u32::from(error)), Ok(error));
+                }
+            }
+
+            #[test]
+            fn test_encoded_size() {
+                for (_, error) in errors() {
+                    assert!(error.encoded_size() <= 4, "{error:?} exceeds 4 bytes");
+                }
+            }
+
+            #[test]
+            fn test_codes_are_unique() {
+                let codes = all_valid_codes();
+                for (_, error) in errors() {
+                    let code = u32::from(error);
+                    assert_eq!(
+                        codes.iter().filter(|other| **other == code).count(),
+                        1,
+                        "{error:?} collides or is missing"
+                    );
+                }
+            }
+
+            #[test]
+            fn test_display() {
+                for (inner, error) in errors() {
+                    assert!(!inner.to_string().is_empty(), "{inner:?} has no message");
+                    assert!(!error.details().is_empty(), "{error:?} has no details");
+                }
+            }
+
+            #[test]
+            fn test_catalogue() {
+                let variants = PopApiError::all_variants();
+                for (inner, error) in errors() {
+                    assert!(variants.contains(&error), "{error:?} not in all_variants");
+                    assert!(
+                        error.path().ends_with(&format!("::{inner:?}")),
+                        "{error:?} has path {}",
+                        error.path()
+                    );
+                    assert!(!error.mnemonic().is_empty());
+                }
+            }
+        }
+    };
+}
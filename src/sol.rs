@@ -0,0 +1,97 @@
+// Solidity style error selectors for contracts using ink!'s Solidity ABI: the
+// first 4 bytes of the keccak256 hash of the error's mnemonic. A selector is
+// distinct from the status code, which is the SCALE encoding of the error, and
+// doesn't include payloads.
+use crate::{ModuleError, PopApiError, UseCaseError};
+use tiny_keccak::{Hasher, Keccak};
+
+impl PopApiError {
+    // The Solidity style selector of the error, e.g. the selector of
+    // `FUNG_INSUF_BAL` for an insufficient fungibles balance.
+    pub fn to_sol_selector(&self) -> [u8; 4] {
+        let mut hasher = Keccak::v256();
+        hasher.update(self.mnemonic().as_bytes());
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    // The error with the given selector, `None` if it isn't known. As payloads
+    // aren't part of a selector, errors with payloads are returned with zeroed
+    // payloads.
+    pub fn from_sol_selector(selector: [u8; 4]) -> Option<Self> {
+        use PopApiError::*;
+        let error = PopApiError::all_variants()
+            .into_iter()
+            .find(|error| error.to_sol_selector() == selector)?;
+        Some(match error {
+            Other(_) => Other(0),
+            Module(_) => Module(ModuleError { index: 0, error: 0 }),
+            UseCase(UseCaseError::Module(_)) => {
+                UseCase(UseCaseError::Module(ModuleError { index: 0, error: 0 }))
+            }
+            Unspecified { .. } => Unspecified {
+                dispatch_error_index: 0,
+                error_index: 0,
+                error: 0,
+            },
+            error => error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArithmeticError, FungiblesError};
+
+    #[test]
+    fn test_selectors_are_unique() {
+        let variants = PopApiError::all_variants();
+        for (i, error) in variants.iter().enumerate() {
+            let selector = error.to_sol_selector();
+            assert!(
+                variants[i + 1..]
+                    .iter()
+                    .all(|other| other.to_sol_selector() != selector),
+                "duplicate selector {selector:?} of {error:?}"
+            );
+        }
+    }
+
+    // Selectors are part of the ABI, changing one breaks existing contracts.
+    #[test]
+    fn test_selectors_are_stable() {
+        let fungibles =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        assert_eq!(fungibles.to_sol_selector(), [98, 33, 155, 223]);
+        assert_eq!(
+            PopApiError::Arithmetic(ArithmeticError::Overflow).to_sol_selector(),
+            [108, 76, 238, 76]
+        );
+    }
+
+    #[test]
+    fn test_from_sol_selector() {
+        for error in PopApiError::all_variants() {
+            let found = PopApiError::from_sol_selector(error.to_sol_selector()).unwrap();
+            assert_eq!(found.mnemonic(), error.mnemonic());
+        }
+        assert_eq!(
+            PopApiError::from_sol_selector(PopApiError::Other(7).to_sol_selector()),
+            Some(PopApiError::Other(0))
+        );
+        let error = PopApiError::UseCase(UseCaseError::Module(ModuleError {
+            index: 150,
+            error: 3,
+        }));
+        assert_eq!(
+            PopApiError::from_sol_selector(error.to_sol_selector()),
+            Some(PopApiError::UseCase(UseCaseError::Module(ModuleError {
+                index: 0,
+                error: 0
+            })))
+        );
+        assert_eq!(PopApiError::from_sol_selector([0; 4]), None);
+    }
+}
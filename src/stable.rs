@@ -0,0 +1,148 @@
+// Support for `assert_encoding_stable!`, which pins the encoding of a value to
+// known bytes so that accidental changes to the wire format fail the tests of
+// any crate that depends on it.
+use parity_scale_codec::Encode;
+
+// Parses the hex form of the expected bytes, e.g. `"0e0008"`. Whitespace is
+// ignored so that longer encodings can be grouped.
+#[doc(hidden)]
+pub fn parse_hex(hex: &str) -> Vec<u8> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .unwrap_or_else(|| panic!("invalid hex digit {c:?} in {hex:?}")) as u8
+        })
+        .collect();
+    assert!(
+        digits.len().is_multiple_of(2),
+        "odd number of hex digits in {hex:?}"
+    );
+    digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The failure message of `assert_encoding_stable!`, `None` if the encoding
+// matches. Both encodings are shown as hex and as a byte array, with a marker
+// under the first byte that differs.
+pub fn mismatch(value: &str, actual: &[u8], expected: &[u8]) -> Option<String> {
+    let position = actual
+        .iter()
+        .zip(expected)
+        .position(|(actual, expected)| actual != expected)
+        .or((actual.len() != expected.len()).then(|| actual.len().min(expected.len())))?;
+    Some(format!(
+        "encoding of `{value}` is not stable\n\
+         expected: {} ({expected:?})\n  \
+         actual: {} ({actual:?})\n\
+         {:>width$} first mismatch at byte {position}",
+        hex(expected),
+        hex(actual),
+        "^^",
+        width = "expected: ".len() + 3 * position + 2,
+    ))
+}
+
+#[doc(hidden)]
+pub fn assert_stable<T: Encode>(name: &str, value: &T, expected: &[u8]) {
+    if let Some(message) = mismatch(name, &value.encode(), expected) {
+        panic!("{message}");
+    }
+}
+
+// Asserts that a value encodes to the expected bytes, given either as a byte
+// array or as a hex string:
+//
+/// ```
+/// use encoding::{assert_encoding_stable, FungiblesError::*, PopApiError::*, UseCaseError::*};
+///
+/// assert_encoding_stable!(BadOrigin, [2]);
+/// assert_encoding_stable!(UseCase(Fungibles(Unknown)), hex "0e0008");
+/// ```
+#[macro_export]
+macro_rules! assert_encoding_stable {
+    ($value:expr, hex $expected:literal) => {
+        $crate::stable::assert_stable(
+            stringify!($value),
+            &$value,
+            &$crate::stable::parse_hex($expected),
+        )
+    };
+    ($value:expr, [$($byte:expr),* $(,)?]) => {
+        $crate::stable::assert_stable(
+            stringify!($value),
+            &$value,
+            &[$($byte),*],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError::*, PopApiError::*, UseCaseError::*};
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("0e0008"), [14, 0, 8]);
+        assert_eq!(parse_hex("0E 00 08"), [14, 0, 8]);
+        assert!(parse_hex("").is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "odd number of hex digits")]
+    fn test_parse_hex_rejects_odd_length() {
+        parse_hex("0e0");
+    }
+
+    #[test]
+    fn test_matching_encoding() {
+        assert_eq!(mismatch("BadOrigin", &[2], &[2]), None);
+        assert_encoding_stable!(BadOrigin, [2]);
+        assert_encoding_stable!(UseCase(Fungibles(Unknown)), hex "0e0008");
+    }
+
+    #[test]
+    fn test_mismatch_message() {
+        assert_eq!(
+            mismatch("error", &[14, 0, 9], &[14, 0, 8]).unwrap(),
+            "encoding of `error` is not stable\n\
+             expected: 0e 00 08 ([14, 0, 8])\n  \
+             actual: 0e 00 09 ([14, 0, 9])\n\
+             \x20               ^^ first mismatch at byte 2"
+        );
+        // A prefix mismatches at the first missing byte.
+        assert!(mismatch("error", &[14, 0], &[14, 0, 8])
+            .unwrap()
+            .ends_with("first mismatch at byte 2"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected: 03 ([3])\n  actual: 02 ([2])\n          ^^ first \
+                               mismatch at byte 0"
+    )]
+    fn test_array_form_failure() {
+        assert_encoding_stable!(BadOrigin, [3]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "encoding of `UseCase(Fungibles(Unknown))` is not stable\nexpected: \
+                               0e 00 07 ([14, 0, 7])\n  actual: 0e 00 08 ([14, 0, 8])"
+    )]
+    fn test_hex_form_failure() {
+        assert_encoding_stable!(UseCase(Fungibles(Unknown)), hex "0e0007");
+    }
+}
@@ -0,0 +1,194 @@
+// Building blocks for monitoring errors, e.g. in a daemon following a chain.
+use crate::{try_decode_from_u32, PopApiError};
+use std::{borrow::Cow, collections::HashMap};
+
+// Everything a structured logger needs to know about an error.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ErrorReport {
+    pub code: u32,
+    // The top-level variant, e.g. `UseCase` or `Module`.
+    pub class: &'static str,
+    pub message: Cow<'static, str>,
+    pub retryable: bool,
+    // Number of occurrences, 1 for the report of a single error.
+    pub count: u64,
+}
+
+impl PopApiError {
+    pub fn report(&self) -> ErrorReport {
+        let path = self.path();
+        ErrorReport {
+            code: u32::from(*self),
+            class: path.split_once("::").map_or(path, |(class, _)| class),
+            message: Cow::Owned(self.to_string()),
+            retryable: self.is_retryable(),
+            count: 1,
+        }
+    }
+}
+
+// Reports of the errors of raw status codes, e.g. taken from logs, with their
+// number of occurrences. Most frequent first, errors occurring equally often
+// are ordered by their status code. Invalid codes are reported with the class
+// `Invalid` and the decoding error as message, instead of failing.
+pub fn summarize_codes(codes: &[u32]) -> Vec<ErrorReport> {
+    let mut counts = HashMap::new();
+    for code in codes {
+        *counts.entry(*code).or_default() += 1;
+    }
+    by_frequency(&counts)
+        .into_iter()
+        .map(|(code, count)| match try_decode_from_u32(code) {
+            Ok(error) => ErrorReport {
+                count,
+                ..error.report()
+            },
+            Err(error) => ErrorReport {
+                code,
+                class: "Invalid",
+                message: Cow::Owned(error.to_string()),
+                retryable: false,
+                count,
+            },
+        })
+        .collect()
+}
+
+// The counts sorted by frequency, ties by status code.
+fn by_frequency(counts: &HashMap<u32, u64>) -> Vec<(u32, u64)> {
+    let mut counts: Vec<_> = counts.iter().map(|(code, count)| (*code, *count)).collect();
+    counts.sort_unstable_by(|(a_code, a), (b_code, b)| b.cmp(a).then(a_code.cmp(b_code)));
+    counts
+}
+
+// Counts errors by their status code.
+#[derive(Debug, Default, Clone)]
+pub struct ErrorCounter {
+    counts: HashMap<u32, u64>,
+}
+
+impl ErrorCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, error: PopApiError) {
+        *self.counts.entry(u32::from(error)).or_default() += 1;
+    }
+
+    // The `n` most frequent errors, most frequent first. Errors occurring
+    // equally often are ordered by their status code.
+    pub fn top_n(&self, n: usize) -> Vec<(PopApiError, u64)> {
+        by_frequency(&self.counts)
+            .into_iter()
+            .take(n)
+            .map(|(code, count)| {
+                // Only codes of recorded errors are counted.
+                let error = try_decode_from_u32(code).expect("recorded codes are valid");
+                (error, count)
+            })
+            .collect()
+    }
+
+    // Number of errors recorded.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FungiblesError, ModuleError, UseCaseError};
+
+    #[test]
+    fn test_top_n() {
+        let balance =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        let module = PopApiError::Module(ModuleError { index: 1, error: 2 });
+        let mut counter = ErrorCounter::new();
+        for error in [
+            balance,
+            PopApiError::BadOrigin,
+            module,
+            balance,
+            module,
+            balance,
+            PopApiError::CannotLookup,
+        ] {
+            counter.record(error);
+        }
+        assert_eq!(counter.total(), 7);
+        assert_eq!(
+            counter.top_n(3),
+            [
+                (balance, 3),
+                (module, 2),
+                // Ties are ordered by status code.
+                (PopApiError::CannotLookup, 1),
+            ]
+        );
+        assert_eq!(counter.top_n(10).len(), 4);
+        assert_eq!(counter.top_n(0), []);
+    }
+
+    #[test]
+    fn test_report() {
+        let error =
+            PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+        assert_eq!(
+            error.report(),
+            ErrorReport {
+                code: u32::from_le_bytes([14, 0, 3, 0]),
+                class: "UseCase",
+                message: "fungibles error: not enough balance to fulfill the request".into(),
+                retryable: false,
+                count: 1,
+            }
+        );
+        let error = PopApiError::Module(ModuleError { index: 1, error: 2 });
+        assert_eq!(
+            error.report(),
+            ErrorReport {
+                code: u32::from_le_bytes([3, 1, 2, 0]),
+                class: "Module",
+                message: "module error: pallet index 1, error index 2".into(),
+                retryable: false,
+                count: 1,
+            }
+        );
+        assert!(PopApiError::Exhausted.report().retryable);
+    }
+
+    #[test]
+    fn test_summarize_codes() {
+        let balance = u32::from(PopApiError::UseCase(UseCaseError::Fungibles(
+            FungiblesError::InsufficientBalance,
+        )));
+        let bad_origin = u32::from(PopApiError::BadOrigin);
+        let invalid = u32::from_le_bytes([14, 0, 10, 0]);
+        let summary = summarize_codes(&[bad_origin, balance, invalid, balance, invalid, balance]);
+        let counts: Vec<_> = summary
+            .iter()
+            .map(|report| (report.code, report.class, report.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                (balance, "UseCase", 3),
+                (invalid, "Invalid", 2),
+                (bad_origin, "BadOrigin", 1)
+            ]
+        );
+        assert_eq!(summary[2].message, "bad origin");
+        assert!(summary[1].message.ends_with("(position: 2)"));
+        assert_eq!(summarize_codes(&[]), []);
+    }
+
+    #[test]
+    fn test_empty_counter() {
+        let counter = ErrorCounter::new();
+        assert_eq!(counter.total(), 0);
+        assert_eq!(counter.top_n(5), []);
+    }
+}
@@ -0,0 +1,128 @@
+// A tour of the SCALE encoding of enums, the building block of status codes:
+// one small example of every kind of variant and payload, with its encoding
+// pinned by the tests. `examples/encoding_tour.rs` renders it as a table,
+// explaining every byte.
+use parity_scale_codec::{Decode, Encode};
+#[cfg(any(test, feature = "explain"))]
+use scale_info::TypeInfo;
+
+// An enum with a variant of every kind. The variant index is encoded as the
+// first byte, followed by the fields in declaration order.
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+pub enum ComprehensiveEnum {
+    /// A unit variant, only the variant index.
+    SimpleVariant,
+    /// A single field, encoded right after the variant index.
+    DataVariant(u8),
+    /// Named fields, encoded just like unnamed ones.
+    NamedFields { w: u8 },
+    /// A nested enum adds the byte of its own variant index.
+    NestedEnum(InnerEnum),
+    /// `None` is `0`, `Some` is `1` followed by the value.
+    OptionVariant(Option<u8>),
+    /// The compact encoded length followed by the elements.
+    VecVariant(Vec<u8>),
+    /// The fields one after the other.
+    TupleVariant(u8, u8),
+    /// A struct is the concatenation of its fields.
+    NestedStructVariant(NestedStruct),
+    /// A struct wrapping an enum.
+    NestedEnumStructVariant(NestedEnumStruct),
+}
+
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+pub enum InnerEnum {
+    A,
+    B { inner_data: u8 },
+    C(u8),
+}
+
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+pub struct NestedStruct {
+    pub x: u8,
+    pub y: u8,
+}
+
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(any(test, feature = "explain"), derive(TypeInfo))]
+pub struct NestedEnumStruct {
+    pub inner_enum: InnerEnum,
+}
+
+// The values of the tour, in the order of the variants.
+pub fn tour_values() -> Vec<ComprehensiveEnum> {
+    use ComprehensiveEnum::*;
+    vec![
+        SimpleVariant,
+        DataVariant(42),
+        NamedFields { w: 42 },
+        NestedEnum(InnerEnum::B { inner_data: 42 }),
+        OptionVariant(Some(42)),
+        OptionVariant(None),
+        VecVariant(vec![1, 2, 3, 4, 5]),
+        VecVariant(vec![]),
+        TupleVariant(42, 42),
+        NestedStructVariant(NestedStruct { x: 42, y: 42 }),
+        NestedEnumStructVariant(NestedEnumStruct {
+            inner_enum: InnerEnum::C(42),
+        }),
+    ]
+}
+
+// Every value of the tour described by its `Debug` output, together with its
+// encoding.
+pub fn scale_tour() -> Vec<(String, Vec<u8>)> {
+    tour_values()
+        .into_iter()
+        .map(|value| (format!("{value:?}"), value.encode()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings() {
+        let encodings: Vec<_> = scale_tour()
+            .into_iter()
+            .map(|(_, encoded)| encoded)
+            .collect();
+        let expected: [&[u8]; 11] = [
+            &[0],
+            &[1, 42],
+            &[2, 42],
+            &[3, 1, 42],
+            &[4, 1, 42],
+            &[4, 0],
+            &[5, 20, 1, 2, 3, 4, 5],
+            &[5, 0],
+            &[6, 42, 42],
+            &[7, 42, 42],
+            &[8, 2, 42],
+        ];
+        assert_eq!(encodings, expected);
+    }
+
+    #[test]
+    fn test_descriptions() {
+        let tour = scale_tour();
+        assert_eq!(tour[0].0, "SimpleVariant");
+        assert_eq!(tour[3].0, "NestedEnum(B { inner_data: 42 })");
+        assert_eq!(tour.len(), tour_values().len());
+    }
+
+    #[test]
+    fn test_values_decode() {
+        for value in tour_values() {
+            let encoded = value.encode();
+            assert_eq!(
+                ComprehensiveEnum::decode(&mut &encoded[..]),
+                Ok(value.clone())
+            );
+        }
+    }
+}
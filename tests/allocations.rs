@@ -0,0 +1,51 @@
+// Converting an error to and from its status code must not allocate, a
+// counting global allocator verifies this.
+use encoding::{try_decode_from_u32, ErrorCode, PopApiError};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Number of allocations on the current thread while running `f`.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn status_code_conversions_do_not_allocate() {
+    for error in PopApiError::all_variants() {
+        let count = allocations(|| {
+            let bytes = error.encode_to_array();
+            let code = u32::from(error);
+            let decoded = PopApiError::try_from(code).unwrap();
+            let strict = try_decode_from_u32(code).unwrap();
+            let from_bytes = PopApiError::from_le_bytes(bytes).unwrap();
+            let trait_code = error.to_u32().unwrap();
+            assert!(bytes[0] == error.code() && decoded == strict && from_bytes == strict);
+            assert!(trait_code == code);
+        });
+        assert_eq!(count, 0, "converting {error:?} allocates");
+    }
+}
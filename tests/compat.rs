@@ -0,0 +1,147 @@
+// Wire compatibility across releases. Every release vendors fixtures in
+// `tests/compat/` listing the status codes it produced together with the
+// error they decode to: a text fixture with the `Debug` output of the error
+// and a binary fixture with its variant name, see `write_fixtures`. All of
+// them must keep decoding to the same error with the current crate.
+use encoding::{all_valid_codes, try_decode_from_u32, PopApiError};
+use parity_scale_codec::{Decode, Encode};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+fn compat_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compat"))
+}
+
+fn fixture_files(extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<_> = fs::read_dir(compat_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    files.sort();
+    files
+}
+
+// The binary fixture format: the magic bytes, the format version and the
+// SCALE encoded `(code, variant name)` pairs, the name being the catalogue
+// path of the error, e.g. `UseCase::Fungibles::NoAccount`. The format is
+// versioned so that later releases can still read fixtures of earlier ones.
+const MAGIC: &[u8; 4] = b"SCFX";
+const FORMAT_VERSION: u8 = 1;
+
+// Writes the pairs of every valid code of the current version.
+fn write_fixtures(path: &Path) -> io::Result<()> {
+    let fixtures: Vec<(u32, String)> = all_valid_codes()
+        .into_iter()
+        .map(|code| {
+            (
+                code,
+                PopApiError::try_from(code).unwrap().path().to_string(),
+            )
+        })
+        .collect();
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(FORMAT_VERSION);
+    fixtures.encode_to(&mut bytes);
+    fs::write(path, bytes)
+}
+
+fn read_fixtures(path: &Path) -> io::Result<Vec<(u32, String)>> {
+    let invalid = |error: &str| io::Error::new(io::ErrorKind::InvalidData, error.to_string());
+    let bytes = fs::read(path)?;
+    let encoded = match bytes.strip_prefix(MAGIC) {
+        Some([FORMAT_VERSION, encoded @ ..]) => encoded,
+        Some(_) => return Err(invalid("unsupported fixture format version")),
+        None => return Err(invalid("not a fixture file")),
+    };
+    Vec::decode(&mut &encoded[..]).map_err(|error| invalid(&error.to_string()))
+}
+
+#[test]
+fn historical_codes_decode_identically() {
+    let mut fixtures = 0;
+    for path in fixture_files("txt") {
+        let content = fs::read_to_string(&path).unwrap();
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            let (code, expected) = line.split_once(' ').unwrap();
+            let code: u32 = code.parse().unwrap();
+            let decoded = try_decode_from_u32(code).unwrap_or_else(|e| {
+                panic!("{}: code {code} no longer decodes: {e}", path.display())
+            });
+            assert_eq!(
+                format!("{decoded:?}"),
+                expected,
+                "{}: code {code} decodes differently",
+                path.display()
+            );
+        }
+        fixtures += 1;
+    }
+    assert!(fixtures > 0, "no compatibility fixtures found");
+}
+
+#[test]
+fn historical_binary_codes_decode_identically() {
+    let files = fixture_files("bin");
+    assert!(!files.is_empty(), "no binary compatibility fixtures found");
+    for path in files {
+        for (code, name) in read_fixtures(&path).unwrap() {
+            let decoded = try_decode_from_u32(code).unwrap_or_else(|e| {
+                panic!("{}: code {code} no longer decodes: {e}", path.display())
+            });
+            assert_eq!(
+                decoded.path(),
+                name,
+                "{}: code {code} decodes differently",
+                path.display()
+            );
+            assert_eq!(u32::from(decoded), code, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn binary_fixtures_round_trip() {
+    let path = std::env::temp_dir().join(format!("scale-fixtures-{}.bin", std::process::id()));
+    write_fixtures(&path).unwrap();
+    let fixtures = read_fixtures(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(fixtures.len(), all_valid_codes().len());
+    assert!(fixtures.contains(&(
+        u32::from_le_bytes([14, 0, 6, 0]),
+        "UseCase::Fungibles::NoAccount".into()
+    )));
+
+    let path = compat_dir().join("v0.1.0.txt");
+    assert_eq!(
+        read_fixtures(&path).unwrap_err().to_string(),
+        "not a fixture file"
+    );
+}
+
+// Emits the fixtures of the current version, run it when cutting a release:
+// `cargo test --test compat -- --ignored`. Fixtures are history: existing ones
+// are never overwritten, bump the version of the crate first.
+#[test]
+#[ignore]
+fn generate_fixture() {
+    let version = env!("CARGO_PKG_VERSION");
+    let text = compat_dir().join(format!("v{version}.txt"));
+    let binary = compat_dir().join(format!("v{version}.bin"));
+    for path in [&text, &binary] {
+        assert!(
+            !path.exists(),
+            "{} already exists, bump the crate version before generating fixtures",
+            path.display()
+        );
+    }
+    let mut content = format!("# Status codes of version {version}.\n");
+    for code in all_valid_codes() {
+        let error = try_decode_from_u32(code).unwrap();
+        content.push_str(&format!("{code} {error:?}\n"));
+    }
+    fs::write(text, content).unwrap();
+    write_fixtures(&binary).unwrap();
+}
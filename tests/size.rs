@@ -0,0 +1,44 @@
+// Contract size regression check. Builds `examples/size-probe`, a minimal
+// contract decoding a status code, for wasm and compares its size against the
+// committed budget. Run with `--features size-check` and `UPDATE_SNAPSHOTS=1`
+// to update the budget after a deliberate size change.
+#![cfg(feature = "size-check")]
+use std::{fs, path::Path, process::Command};
+
+// Growth beyond the budget that is tolerated, in percent.
+const TOLERANCE: u64 = 5;
+
+#[test]
+fn size_probe_within_budget() {
+    let probe = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/size-probe"));
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+        .current_dir(probe)
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the size probe failed");
+    let wasm = probe.join("target/wasm32-unknown-unknown/release/size_probe.wasm");
+    let size = fs::metadata(wasm).unwrap().len();
+
+    let budget_file = probe.join("budget.txt");
+    let content = fs::read_to_string(&budget_file).unwrap();
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        let header: String = content
+            .lines()
+            .filter(|line| line.starts_with('#'))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        fs::write(&budget_file, format!("{header}{size}\n")).unwrap();
+        return;
+    }
+    let budget: u64 = content
+        .lines()
+        .find(|line| !line.starts_with('#'))
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(
+        size <= budget + budget * TOLERANCE / 100,
+        "size probe is {size} bytes, exceeding the budget of {budget} bytes by more than {TOLERANCE}%"
+    );
+}
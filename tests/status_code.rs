@@ -0,0 +1,85 @@
+// A contract's own errors packed into status codes with
+// `#[derive(ToStatusCode)]`, next to the status codes of `PopApiError`.
+use encoding::{
+    all_valid_codes, is_reserved_code, max_known_variant, try_decode_from_u32, ModuleError,
+    PopApiError, ToStatusCode, UseCaseError,
+};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode, MaxEncodedLen, ToStatusCode)]
+#[status_code(offset = 16)]
+enum ContractError {
+    NotOwner,
+    Paused,
+    LimitExceeded(u16),
+    Custom(u8),
+}
+
+// What a contract receives: either error of the runtime or one of its own.
+#[derive(Debug, PartialEq)]
+enum Status {
+    Runtime(PopApiError),
+    Contract(ContractError),
+}
+
+fn decode(code: u32) -> Option<Status> {
+    if is_reserved_code(code) {
+        ContractError::try_from(code).ok().map(Status::Contract)
+    } else {
+        try_decode_from_u32(code).ok().map(Status::Runtime)
+    }
+}
+
+fn contract_errors() -> Vec<ContractError> {
+    use ContractError::*;
+    vec![
+        NotOwner,
+        Paused,
+        LimitExceeded(0),
+        LimitExceeded(u16::MAX),
+        Custom(7),
+    ]
+}
+
+#[test]
+fn codes_round_trip() {
+    for error in contract_errors() {
+        let code = u32::from(error);
+        assert_eq!(ContractError::try_from(code), Ok(error));
+        assert_eq!(decode(code), Some(Status::Contract(error)));
+    }
+    assert_eq!(u32::from(ContractError::LimitExceeded(0x0201)), 0x0002_0112);
+}
+
+// The offset places the codes right above the variants of `PopApiError`.
+#[test]
+fn codes_are_above_pop_api_error() {
+    assert_eq!(
+        u32::from(ContractError::NotOwner).to_le_bytes()[0],
+        max_known_variant() + 1
+    );
+    for error in contract_errors() {
+        let code = u32::from(error);
+        assert!(is_reserved_code(code), "{error:?}");
+        assert!(try_decode_from_u32(code).is_err(), "{error:?}");
+    }
+    for code in all_valid_codes() {
+        assert!(ContractError::try_from(code).is_err(), "{code}");
+        assert!(matches!(decode(code), Some(Status::Runtime(_))));
+    }
+    let error = PopApiError::UseCase(UseCaseError::Module(ModuleError {
+        index: 150,
+        error: 3,
+    }));
+    assert_eq!(decode(u32::from(error)), Some(Status::Runtime(error)));
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    let error = |code: u32| ContractError::try_from(code).unwrap_err().to_string();
+    // `Paused` with non-zero padding.
+    assert_eq!(error(0x0100_0011), "Status code has non-zero padding");
+    assert_eq!(error(2), "Status code is below the offset");
+    // Unknown variant.
+    assert!(ContractError::try_from(20).is_err());
+}
@@ -0,0 +1,70 @@
+// Interop with `subxt`, the client library most off-chain tools use. subxt
+// decodes the `DispatchError` of a failed extrinsic with the runtime's SCALE
+// layout, so a module error reaches the client as the variant index `3`
+// followed by the pallet index and the 4 error bytes. subxt exposes the latter
+// 5 bytes of the module error as `[pallet_index, error[0], error[1],
+// error[2], error[3]]`, where `error[0]` is the index of the pallet's error
+// variant and the remaining bytes encode its fields, if any. These tests build
+// the bytes following that convention, without depending on subxt itself.
+#![cfg(feature = "runtime")]
+
+use encoding::{
+    refine_module_error, FungiblesError, ModuleError, PopApiError, UseCaseError,
+    ASSETS_PALLET_INDEX, SYSTEM_PALLET_INDEX,
+};
+use parity_scale_codec::Decode;
+use sp_runtime::DispatchError;
+
+// The variant index of `DispatchError::Module`.
+const MODULE_ERROR_INDEX: u8 = 3;
+
+// The 5 bytes of a module error as subxt presents them.
+fn subxt_module_error(pallet_index: u8, error: [u8; 4]) -> [u8; 5] {
+    let [a, b, c, d] = error;
+    [pallet_index, a, b, c, d]
+}
+
+// Decodes the module error like a client would: the bytes are the fields of
+// `DispatchError::Module`.
+fn decode(bytes: [u8; 5]) -> PopApiError {
+    let mut encoded = vec![MODULE_ERROR_INDEX];
+    encoded.extend(bytes);
+    let error = DispatchError::decode(&mut &encoded[..]).unwrap();
+    PopApiError::from(error)
+}
+
+#[test]
+fn assets_error_decodes_to_use_case() {
+    // `pallet_assets::Error::BalanceLow`.
+    let bytes = subxt_module_error(ASSETS_PALLET_INDEX, [0, 0, 0, 0]);
+    let expected =
+        PopApiError::UseCase(UseCaseError::Fungibles(FungiblesError::InsufficientBalance));
+    assert_eq!(decode(bytes), expected);
+    // Clients holding only the 5 bytes refine them directly.
+    let error = ModuleError {
+        index: bytes[0],
+        error: bytes[1],
+    };
+    assert_eq!(refine_module_error(error), expected);
+}
+
+#[test]
+fn system_error_decodes_to_top_level_error() {
+    // `frame_system::Error::CallFiltered`.
+    let bytes = subxt_module_error(SYSTEM_PALLET_INDEX, [5, 0, 0, 0]);
+    assert_eq!(decode(bytes), PopApiError::BadOrigin);
+}
+
+#[test]
+fn unknown_pallet_error_stays_a_module_error() {
+    // The error bytes following the variant index, i.e. the fields of the
+    // pallet's error, don't fit a status code and are dropped.
+    let bytes = subxt_module_error(200, [7, 1, 2, 3]);
+    assert_eq!(
+        decode(bytes),
+        PopApiError::Module(ModuleError {
+            index: 200,
+            error: 7
+        })
+    );
+}